@@ -4,26 +4,52 @@ use proc_macro2::Span;
 use quote::quote;
 use syn::*;
 
-// FIXME remove this
-const GRID_SPACING: f32 = 250.0;
-const HALFSIZE: f32 = GRID_SPACING / 2.0;
+// the width of a column (or the height of a row) on an 800x800 window, unless #[dimension(spacing = ...)] says otherwise
+const DEFAULT_SPACING: f32 = 250.0;
 
 macro_rules! derive_error {
-    ($string: tt) => {
-        Error::new(Span::call_site(), $string)
+    ($message: expr) => {
+        Error::new(Span::call_site(), $message)
             .to_compile_error()
             .into()
     };
 }
 
-#[proc_macro_derive(Dimension)]
+#[proc_macro_derive(Dimension, attributes(dimension))]
 pub fn dimension_macro_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
     impl_dimension_macro(&ast)
 }
 
+// a Dimension is a row or a column of the 3x3 board, so it must have exactly three variants, from bottom to top (or left
+// to right), as values(), range(), and containing() are all laid out for a board three cells across
+// any other number of variants is a compile error, naming the enum and how many variants it has
+//
+// each cell is `spacing` pixels across this dimension, which can be set with #[dimension(spacing = 200.0)], so that rows
+// and columns can differ, and cells don't have to be square
 fn impl_dimension_macro(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
+
+    let mut spacing = DEFAULT_SPACING;
+
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("dimension")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("spacing") {
+                return Err(meta.error("the only dimension attribute is spacing, e.g. #[dimension(spacing = 250.0)]"));
+            }
+
+            spacing = meta.value()?.parse::<LitFloat>()?.base10_parse()?;
+
+            if spacing > 0.0 { Ok(()) } else { Err(meta.error("spacing must be greater than zero")) }
+        });
+
+        if let Err(error) = parsed {
+            return error.to_compile_error().into();
+        }
+    }
+
+    let halfsize = spacing / 2.0;
+
     match &ast.data {
         Data::Enum(data_enum) => {
             match data_enum.variants.len() {
@@ -33,11 +59,13 @@ fn impl_dimension_macro(ast: &syn::DeriveInput) -> TokenStream {
 
                     let gen = quote! {
                         impl #name {
-                            fn values() -> [#name; 3] {
+                            pub const SPACING: f32 = #spacing;
+
+                            pub fn values() -> [#name; 3] {
                                 [ #name::#first, #name::#second, #name::#third ]
                             }
 
-                            fn position(&self) -> i8 {
+                            pub fn position(&self) -> i8 {
                                 match self {
                                     #name::#first => -1,
                                     #name::#second => 0,
@@ -45,20 +73,20 @@ fn impl_dimension_macro(ast: &syn::DeriveInput) -> TokenStream {
                                 }
                             }
 
-                            fn range(&self) -> Vec2 {
+                            pub fn range(&self) -> Vec2 {
                                 match self {
-                                    #name::#first => Vec2::new(-3.0*#HALFSIZE, -#HALFSIZE),
-                                    #name::#second => Vec2::new(-#HALFSIZE, #HALFSIZE),
-                                    #name::#third => Vec2::new(#HALFSIZE, 3.0*#HALFSIZE),
+                                    #name::#first => Vec2::new(-3.0*#halfsize, -#halfsize),
+                                    #name::#second => Vec2::new(-#halfsize, #halfsize),
+                                    #name::#third => Vec2::new(#halfsize, 3.0*#halfsize),
                                 }
                             }
 
-                            fn in_range(&self, value: f32) -> bool {
+                            pub fn in_range(&self, value: f32) -> bool {
                                 let Vec2 { x: min, y: max } = self.range();
                                 min <= value && value < max
                             }
 
-                            fn containing(value: f32) -> Option<#name> {
+                            pub fn containing(value: f32) -> Option<#name> {
                                 if #name::#first.in_range(value) {
                                     Some(#name::#first)
                                 } else if #name::#second.in_range(value) {
@@ -74,7 +102,7 @@ fn impl_dimension_macro(ast: &syn::DeriveInput) -> TokenStream {
 
                     gen.into()
                 }
-                _ => return derive_error!("Dimension requires an enum with exactly three variants")
+                n => return derive_error!(format!("Dimension requires an enum with exactly three variants, but {} has {}", name, n))
             }
         }
         _ => return derive_error!("Dimension is only implemented for enums")
@@ -131,6 +159,12 @@ fn impl_enumerated_macro(ast: &syn::DeriveInput) -> TokenStream {
             let variants = data_enum.variants.iter().map(|each| quote!(#name::#each));
             let cardinality = variants.len();
 
+            // each variant, and the variant after it, wrapping around from the last variant to the first
+            let all = data_enum.variants.iter().collect::<Vec<_>>();
+            let following = all.iter().cycle().skip(1).take(all.len());
+            let (from, to) = (all.iter(), following.clone());
+            let (prev_from, prev_to) = (following, all.iter());
+
             let gen = quote! {
                 impl Enumerated for #name {
                     type Item = #name;
@@ -140,6 +174,18 @@ fn impl_enumerated_macro(ast: &syn::DeriveInput) -> TokenStream {
                     fn variants() -> Vec<Self::Item> {
                         vec![#(#variants), *]
                     }
+
+                    fn next(&self) -> Self::Item {
+                        match self {
+                            #(#name::#from => #name::#to), *
+                        }
+                    }
+
+                    fn prev(&self) -> Self::Item {
+                        match self {
+                            #(#name::#prev_from => #name::#prev_to), *
+                        }
+                    }
                 }
             };
 
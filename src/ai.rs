@@ -0,0 +1,592 @@
+use bevy::log::info;
+use rand::prelude::*;
+
+use crate::board::{Cell, Game, Line, Mark};
+use crate::Enumerated;
+use crate::grid::Grid;
+use crate::rng::GameRng;
+use crate::settings::{CenterBonus, Difficulty, FirstMove, PlayStyle, ThreatPriority, TieBreak};
+
+// the settings which change how the computer chooses its moves, other than its Difficulty
+#[derive(Clone, Copy, Default)]
+pub struct ComputerOptions {
+    pub tie_break: TieBreak,
+    pub center_bonus: CenterBonus,
+    pub first_move: FirstMove,
+    pub play_style: PlayStyle,
+    pub threat_priority: ThreatPriority,
+}
+
+// on Hard, the first move of each player comes from a small "opening book", so that not every game looks the same
+//
+// any first move draws with perfect play, so X picks at random between a corner, an edge, and the center (as the
+// FirstMove house rule allows)
+// O picks at random from the replies to X's first move which still guarantee (at least) a draw
+fn opening_book(game: &Game, computer: Mark, first_move: FirstMove, rng: &mut GameRng) -> Option<Cell> {
+    book_moves(game, computer, first_move).choose(rng).copied()
+}
+
+// every move the opening_book() could pick from, which is none at all after the first two moves of a game
+fn book_moves(game: &Game, computer: Mark, first_move: FirstMove) -> Vec<Cell> {
+    let moves = game.distinct_moves();
+
+    match game.history().len() {
+        0 => moves.into_iter().filter(|cell| first_move.allows(*cell)).collect(),
+        1 => {
+            let scores: Vec<i8> = moves.iter().map(|cell| game.with_move(*cell, computer).minimax(computer)).collect();
+            let Some(&best) = scores.iter().max() else { return vec![] };
+
+            moves.into_iter().zip(scores).filter(|(_, score)| *score == best).map(|(cell, _)| cell).collect()
+        }
+        _ => vec![]
+    }
+}
+
+// returns None if there is no legal move for the computer to make (i.e. the board is full)
+//
+// game_seed should be drawn from the GameRng once at the start of each game, and passed in for every move of that game
+// Medium's best-or-worst choice for each move is derived from it, so a game plays out the same way for the same seed
+pub fn generate_computer_input(game: &Game, computer: Mark, difficulty: Difficulty, options: ComputerOptions, game_seed: u64, rng: &mut GameRng) -> Option<Cell> {
+
+    // with PlayStyle::Mirror, the human's last move is copied on the other side of the board, whenever that cell is free
+    if options.play_style == PlayStyle::Mirror {
+        if let Some(cell) = game.history().last().map(Cell::mirrored).filter(|cell| game.get(*cell).is_none()) {
+            info!("computer mirrored the last move with {:?}", cell);
+            return Some(cell);
+        }
+    }
+
+    if difficulty == Difficulty::Hard {
+        if let Some(cell) = opening_book(game, computer, options.first_move, rng) {
+            info!("computer chose {:?} from the opening book", cell);
+            return Some(cell);
+        }
+    }
+
+    let (chosen_cell, _) = *rank_cells(game, computer, difficulty, options, game_seed).first()?;
+
+    info!("optimal cell for computer to choose is {:?} (on {} mode)", chosen_cell, difficulty);
+
+    Some(chosen_cell)
+}
+
+// when practising against a recorded game (see recording.rs), the move which was made next in the recording, as long as
+// the game so far has gone exactly the same way
+pub fn recorded_move(game: &Game, moves: &[Cell]) -> Option<Cell> {
+    let played = game.history();
+
+    if !moves.starts_with(played) { return None; }
+
+    moves.get(played.len()).copied().filter(|cell| game.get(*cell).is_none())
+}
+
+// after this many losses in a row, a Forgiving computer starts going easy on the human
+pub const FORGIVE_AFTER: usize = 3;
+
+// with Forgiveness::Forgiving, half of the time, the computer passes up a winning move for one which doesn't win (if
+// there is one), to keep things close for a human who keeps losing
+//
+// it prefers a move which the human can still draw against, then one which still wins later, and throws the game only
+// when it has to, as losing on purpose would be just as obvious as winning every time
+pub fn forgive(game: &Game, computer: Mark, chosen: Cell, rng: &mut GameRng) -> Cell {
+    let wins = |cell: &Cell| game.with_move(*cell, computer).winner().is_some();
+
+    if !wins(&chosen) || rng.gen_bool(0.5) { return chosen; }
+
+    let others = Cell::variants().into_iter().filter(|cell| game.get(*cell).is_none() && !wins(cell));
+
+    let Some(other) = others.max_by_key(|cell| match game.with_move(*cell, computer).minimax(computer) {
+        0 => 2,
+        1 => 1,
+        _ => 0,
+    }) else { return chosen; };
+
+    info!("computer passed up a win at {:?} for {:?}, to go easy on the human", chosen, other);
+
+    other
+}
+
+// with Teaching::Explain, a short note on the move `mark` is about to make at `cell`, comparing it to what perfect play
+// would have done, or None if there's nothing worth saying (most moves, and any winning move)
+pub fn explain_move(game: &Game, cell: Cell, mark: Mark) -> Option<String> {
+    let opponent = if mark == Mark::X { Mark::O } else { Mark::X };
+
+    let wins_for = |player: Mark| Cell::variants().into_iter()
+        .filter(|cell| game.get(*cell).is_none() && game.with_move(*cell, player).winner().is_some())
+        .collect::<Vec<Cell>>();
+
+    let (wins, threats) = (wins_for(mark), wins_for(opponent));
+    let next = game.with_move(cell, mark);
+
+    if next.winner().is_some() { return None; }
+
+    if let Some(win) = wins.first() {
+        return Some(format!("missed a win at {}", win.name()));
+    }
+
+    if threats.contains(&cell) {
+        return Some(format!("blocked {}'s threat", opponent));
+    }
+
+    if let Some(threat) = threats.first() {
+        return Some(format!("missed {}'s threat at {}", opponent, threat.name()));
+    }
+
+    if game.forks(mark).contains(&cell) {
+        return Some("set up a fork".to_string());
+    }
+
+    // both sides playing perfectly from before and after this move
+    match (game.minimax(mark), next.minimax(mark)) {
+        (1, 0) => Some("let a sure win slip".to_string()),
+        (_, -1) => Some(format!("{} can force a win now", opponent)),
+        _ => None,
+    }
+}
+
+// every empty cell, paired with its weight, from the computer's first choice to its last (ignoring the opening book)
+pub fn rank_cells(game: &Game, computer: Mark, difficulty: Difficulty, options: ComputerOptions, game_seed: u64) -> Vec<(Cell, i8)> {
+
+    // weight cells based on their advantage to the computer and their disadvantage to the human
+    //
+    //   1. +20 for any cell which lets the computer win this turn
+    //   2. +10 for any cell which blocks a human win this turn
+    //      (these two are swapped with ThreatPriority::BlockFirst, except on Hard, and then the block isn't scaled by
+    //      difficulty, so that even Easy blocks)
+    //   3. +2 for the middle-middle space (unless CenterBonus::NoCenterBonus)
+    //   4. +1 for any corner space
+    //   5. -50 on Hard, for any cell after which the human can force a win, so that Hard never loses, and the weights
+    //      above only choose between cells which are just as safe (so Hard is as strong without the center bonus)
+    //
+    // ...then, just pick the cell with the highest weight, after filtering out already-occupied cells
+
+    let mut weights: [i8;9] = [0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    // any random choices are made the same way for the same move of the same game
+    let mut move_rng = GameRng::seeded(game_seed.wrapping_add(game.history().len() as u64));
+
+    // scale weights based on difficulty, so the computer picks non-optimal moves
+
+    let scale = match difficulty {
+        Difficulty::Easy => -1, // purposefully pick the worst possible moves
+        Difficulty::Medium => {
+            // randomly pick best-possible and worst-possible moves
+            *[-1, 1].choose(&mut move_rng).expect("array is non-empty, so we should always get a value")
+        },
+        Difficulty::Hard => 1, // pick the best possible moves
+    };
+
+    let (win, block) = match options.threat_priority {
+        ThreatPriority::BlockFirst if difficulty != Difficulty::Hard => (10 * scale, 20),
+        _ => (20 * scale, 10 * scale),
+    };
+
+    fn index(cell: Cell) -> usize {
+        match cell {
+            Cell::TopLeft => 0,
+            Cell::TopMiddle => 1,
+            Cell::TopRight => 2,
+            Cell::MiddleLeft => 3,
+            Cell::MiddleMiddle => 4,
+            Cell::MiddleRight => 5,
+            Cell::BottomLeft => 6,
+            Cell::BottomMiddle => 7,
+            Cell::BottomRight => 8,
+        }
+    }
+
+    Line::variants().iter().for_each(|line| {
+        let cells_and_marks = line.cells().map(|cell| (cell, game.get(cell)));
+
+        // case (1)
+        match cells_and_marks {
+            [(_, Some(a)), (_, Some(b)), (cell, None)] if a == b && b == computer => weights[index(cell)] += win,
+            [(_, Some(a)), (cell, None), (_, Some(b))] if a == b && b == computer => weights[index(cell)] += win,
+            [(cell, None), (_, Some(a)), (_, Some(b))] if a == b && b == computer => weights[index(cell)] += win,
+            _ => {}
+        }
+
+        // case (2)
+        match cells_and_marks {
+            [(_, Some(a)), (_, Some(b)), (cell, None)] if a == b && b != computer => weights[index(cell)] += block,
+            [(_, Some(a)), (cell, None), (_, Some(b))] if a == b && b != computer => weights[index(cell)] += block,
+            [(cell, None), (_, Some(a)), (_, Some(b))] if a == b && b != computer => weights[index(cell)] += block,
+            _ => {}
+        }
+
+        // case (3)
+        match cells_and_marks {
+            [_, (cell, None), _] if cell == Cell::MiddleMiddle && options.center_bonus == CenterBonus::CenterBonus => weights[index(cell)] += 2 * scale,
+            _ => {}
+        }
+
+        // case (4)
+        match cells_and_marks {
+            [(c1, None), _, (c2, None)] if c1.is_corner() => {
+                weights[index(c1)] += scale;
+                weights[index(c2)] += scale
+            },
+            [(cell, None), _, _] if cell.is_corner() => weights[index(cell)] += scale,
+            [_, _, (cell, None)] if cell.is_corner() => weights[index(cell)] += scale,
+            _ => {}
+        }
+    });
+
+    // case (5)
+    if difficulty == Difficulty::Hard {
+        for cell in Cell::variants().into_iter().filter(|cell| game.get(*cell).is_none()) {
+            if game.with_move(cell, computer).minimax(computer) < 0 {
+                weights[index(cell)] -= 50;
+            }
+        }
+    }
+
+    info!("cell weights (higher is better): {:?}", weights);
+
+    let mut ranked = Cell::variants().into_iter().zip(weights)
+        .filter(|(cell, _)| game.get(*cell).is_none())
+        .filter(|(cell, _)| !game.history().is_empty() || options.first_move.allows(*cell))
+        .collect::<Vec<(Cell, i8)>>();
+
+    // put cells with the same weight in the order of the TieBreak, then sort by weight, which keeps that order
+    match options.tie_break {
+        TieBreak::LastFound => ranked.reverse(),
+        TieBreak::FirstFound => {}
+        TieBreak::Random => ranked.shuffle(&mut move_rng),
+        TieBreak::PreferCenter => ranked.sort_by_key(|(cell, _)| *cell != Cell::MiddleMiddle),
+        TieBreak::PreferCorner => ranked.sort_by_key(|(cell, _)| !cell.is_corner()),
+    }
+
+    ranked.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+
+    ranked
+}
+
+// the computer player for the larger (N x N) boards, where the 3x3 heuristic above doesn't apply
+//
+// weights are the same idea, generalized to lines of any length
+//
+//   1. +20 for any cell which completes a line for the computer (a win this turn)
+//   2. +10 for any cell which blocks a line the human is about to complete
+//   3. +1 for each computer mark in each line through a cell which the human hasn't blocked (building towards a win)
+//   4. +1 for each human mark in each line through a cell which the computer hasn't blocked (spoiling the human's line)
+//
+// ...but these only break ties between cells which score the same when looking ahead with search(), up to max_depth
+// moves deep (see search_depth())
+pub fn generate_grid_input(grid: &Grid, computer: Mark, difficulty: Difficulty, game_seed: u64, max_depth: Option<usize>) -> Option<usize> {
+    let scale = match difficulty {
+        Difficulty::Easy => -1,
+        Difficulty::Medium => {
+            let mut move_rng = GameRng::seeded(game_seed.wrapping_add(grid.history().len() as u64));
+            *[-1, 1].choose(&mut move_rng).expect("array is non-empty, so we should always get a value")
+        },
+        Difficulty::Hard => 1,
+    };
+
+    let mut weights = vec![0; grid.size() * grid.size()];
+
+    for line in grid.lines() {
+        let mine = line.iter().filter(|cell| grid.get(**cell) == Some(computer)).count() as i32;
+        let theirs = line.iter().filter(|cell| matches!(grid.get(**cell), Some(mark) if mark != computer)).count() as i32;
+        let empty = line.len() as i32 - mine - theirs;
+
+        for cell in line.iter().filter(|cell| grid.get(**cell).is_none()) {
+            match (mine, theirs) {
+                (_, 0) if empty == 1 => weights[*cell] += 20 * scale, // case (1)
+                (0, _) if empty == 1 => weights[*cell] += 10 * scale, // case (2)
+                (mine, 0) => weights[*cell] += mine * scale, // case (3)
+                (0, theirs) => weights[*cell] += theirs * scale, // case (4)
+                _ => {}
+            }
+        }
+    }
+
+    info!("cell weights (higher is better): {:?}", weights);
+
+    let scores = grid.empty_cells().into_iter()
+        .map(|cell| (cell, scale * search(&grid.with_move(cell, computer), computer, max_depth.map(|depth| depth.saturating_sub(1)))))
+        .collect::<Vec<(usize, i32)>>();
+
+    info!("cell scores, looking {} (higher is better): {:?}", match max_depth {
+        None => "to the end of the game".to_string(),
+        Some(depth) => format!("{} moves ahead", depth),
+    }, scores);
+
+    let (chosen_cell, _) = scores.into_iter()
+        .max_by_key(|(cell, score)| (*score, weights[*cell]))?;
+
+    info!("optimal cell for computer to choose is {} (on {} mode)", chosen_cell, difficulty);
+
+    Some(chosen_cell)
+}
+
+// how many moves ahead the computer looks on a board with `size` cells along each side, where None means "to the end"
+//
+// a full search of a 3x3 board is quick, but every extra cell multiplies the work, so larger boards look only a few
+// moves ahead, and estimate how good the position is from there, with evaluate()
+pub fn search_depth(size: usize) -> Option<usize> {
+    match size {
+        0..=3 => None,
+        _ => Some(3),
+    }
+}
+
+// a rough score for a position which isn't over yet, from the point of view of `player`, using the same idea as the
+// weights in generate_grid_input(): +1 for each of `player`'s marks in a line which the opponent hasn't blocked, and -1
+// for each of the opponent's marks in a line which `player` hasn't blocked
+fn evaluate(grid: &Grid, player: Mark) -> i32 {
+    grid.lines().into_iter()
+        .map(|line| {
+            let mine = line.iter().filter(|cell| grid.get(**cell) == Some(player)).count() as i32;
+            let theirs = line.iter().filter(|cell| matches!(grid.get(**cell), Some(mark) if mark != player)).count() as i32;
+
+            match (mine, theirs) {
+                (mine, 0) => mine,
+                (0, theirs) => -theirs,
+                _ => 0
+            }
+        })
+        .sum()
+}
+
+// scores `grid` for `player` like Game::minimax(), but only looks `depth` moves ahead (if given), and uses evaluate()
+// for any position which isn't over by then
+//
+// wins and losses score beyond anything evaluate() can return, and quicker wins (and slower losses) score higher
+fn search(grid: &Grid, player: Mark, depth: Option<usize>) -> i32 {
+    const WIN: i32 = 1000;
+
+    let remaining = grid.empty_cells().len() as i32;
+
+    match grid.winner() {
+        Some((winner, _)) if winner == player => return WIN + remaining,
+        Some(_) => return -WIN - remaining,
+        None if grid.over() => return 0,
+        None if depth == Some(0) => return evaluate(grid, player),
+        None => {}
+    }
+
+    let to_move = grid.next_player();
+
+    let scores = grid.empty_cells().into_iter()
+        .map(|cell| search(&grid.with_move(cell, to_move), player, depth.map(|depth| depth - 1)));
+
+    let best = if to_move == player { scores.max() } else { scores.min() };
+
+    best.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the computer's move on the board given in notation (see Game::from_notation()), for whoever is to move there
+    fn computer_move(notation: &str, difficulty: Difficulty, options: ComputerOptions) -> Option<Cell> {
+        let game = Game::from_notation(notation).unwrap();
+        generate_computer_input(&game, game.next_player(), difficulty, options, 0, &mut GameRng::seeded(0))
+    }
+
+    #[test]
+    fn hard_takes_the_winning_cell() {
+        // O to move, and O can win at the top right, or block X at the middle right
+        assert_eq!(computer_move("OO.XX...X", Difficulty::Hard, ComputerOptions::default()), Some(Cell::TopRight));
+    }
+
+    #[test]
+    fn hard_blocks_the_threatened_cell() {
+        // O to move, and X is about to complete the top row
+        assert_eq!(computer_move("XX..O....", Difficulty::Hard, ComputerOptions::default()), Some(Cell::TopRight));
+    }
+
+    #[test]
+    fn block_first_blocks_even_on_easy_and_medium() {
+        let options = ComputerOptions { threat_priority: ThreatPriority::BlockFirst, ..ComputerOptions::default() };
+
+        // O to move, and X is about to complete the top row
+        let game = Game::from_notation("XX..O....").unwrap();
+
+        // Medium plays its best move for some seeds, and its worst for others, but should block either way
+        for difficulty in [Difficulty::Easy, Difficulty::Medium] {
+            for game_seed in 0..20 {
+                let cell = generate_computer_input(&game, Mark::O, difficulty, options, game_seed, &mut GameRng::seeded(0));
+                assert_eq!(cell, Some(Cell::TopRight), "on {} with game seed {}", difficulty, game_seed);
+            }
+        }
+    }
+
+    // like the tournament's MOVE_TIME_LIMIT, but generous enough for a debug build, so that only an accidental blowup in
+    // the AI's running time (e.g. a search which is no longer cut short) fails this
+    #[test]
+    fn hard_moves_quickly() {
+        const LIMIT: std::time::Duration = std::time::Duration::from_secs(10);
+
+        // an empty board (from the opening book) and a game part of the way through
+        for notation in [".........", "X...O...X"] {
+            let start = std::time::Instant::now();
+            assert!(computer_move(notation, Difficulty::Hard, ComputerOptions::default()).is_some());
+            assert!(start.elapsed() < LIMIT, "took {:?} to move on \"{}\"", start.elapsed(), notation);
+        }
+    }
+
+    // a simple strategy, which doesn't look ahead: win if possible, otherwise block, otherwise take the center, or a corner
+    fn basic_move(game: &Game, mark: Mark) -> Cell {
+        let empty = Cell::variants().into_iter().filter(|cell| game.get(*cell).is_none()).collect::<Vec<Cell>>();
+        let opponent = if mark == Mark::X { Mark::O } else { Mark::X };
+
+        let completes = |player: Mark| empty.iter().copied().find(|cell| game.with_move(*cell, player).winner().is_some());
+
+        completes(mark)
+            .or_else(|| completes(opponent))
+            .or_else(|| empty.iter().copied().find(|cell| *cell == Cell::MiddleMiddle))
+            .or_else(|| empty.iter().copied().find(Cell::is_corner))
+            .or_else(|| empty.first().copied())
+            .expect("the game is over when the board is full, so there should always be a legal move")
+    }
+
+    // plays a single game of Easy (as `easy_mark`) against basic_move(), returning the winning mark, if there is one
+    fn play_basic(easy_mark: Mark, rng: &mut GameRng) -> Option<Mark> {
+        let mut game = Game::default();
+        let game_seed = rng.next_u64();
+
+        while !game.over() {
+            let mark = game.next_player();
+
+            let cell = if mark == easy_mark {
+                generate_computer_input(&game, mark, Difficulty::Easy, ComputerOptions::default(), game_seed, rng)
+                    .expect("the game is over when the board is full, so there should always be a legal move")
+            } else {
+                basic_move(&game, mark)
+            };
+
+            game.set(cell, mark);
+        }
+
+        game.winner().map(|(winner, _)| winner)
+    }
+
+    // Easy should be easy: against the sort of thing a beginner does, it should (almost) never win, and lose most games,
+    // so that a change to the AI (e.g. making Easy play randomly, rather than badly) can't quietly make it too strong
+    #[test]
+    fn easy_is_easily_beaten_by_basic_play() {
+        const GAMES: usize = 200;
+
+        let mut rng = GameRng::seeded(0);
+        let (mut wins, mut losses) = (0, 0);
+
+        for index in 0..GAMES {
+            // Easy alternates between X and O
+            let easy_mark = if index % 2 == 0 { Mark::X } else { Mark::O };
+
+            match play_basic(easy_mark, &mut rng) {
+                Some(winner) if winner == easy_mark => wins += 1,
+                Some(_) => losses += 1,
+                None => {}
+            }
+        }
+
+        assert!(wins * 50 <= GAMES, "Easy won {} of {} games against basic play", wins, GAMES);
+        assert!(losses * 2 >= GAMES, "Easy lost only {} of {} games against basic play", losses, GAMES);
+    }
+
+    #[test]
+    fn no_move_on_a_full_board() {
+        let game = Game::from_notation("XOXXOOOXX").unwrap();
+
+        for difficulty in Difficulty::variants() {
+            assert_eq!(generate_computer_input(&game, Mark::X, difficulty, ComputerOptions::default(), 0, &mut GameRng::seeded(0)), None, "on {}", difficulty);
+        }
+    }
+
+    #[test]
+    fn medium_plays_the_same_game_for_the_same_seed() {
+        // Medium vs Medium, where only the game seed is the same
+        let play = |game_seed: u64, rng_seed: u64| {
+            let mut game = Game::default();
+            let mut rng = GameRng::seeded(rng_seed);
+
+            while !game.over() {
+                let mark = game.next_player();
+                let cell = generate_computer_input(&game, mark, Difficulty::Medium, ComputerOptions::default(), game_seed, &mut rng).unwrap();
+                game.set(cell, mark);
+            }
+
+            game
+        };
+
+        for game_seed in 0..10 {
+            assert!(play(game_seed, 1) == play(game_seed, 2), "with game seed {}", game_seed);
+        }
+    }
+
+    #[test]
+    fn hard_plays_a_3x3_grid_perfectly() {
+        // a 3x3 grid is searched to the end, however deep that is, so Hard against itself always draws
+        assert_eq!(search_depth(3), None);
+        assert_eq!(search_depth(4), Some(3));
+
+        for opening in 0..9 {
+            let mut grid = Grid::new(3).with_move(opening, Mark::X);
+
+            while !grid.over() {
+                let mark = grid.next_player();
+                let cell = generate_grid_input(&grid, mark, Difficulty::Hard, 0, search_depth(3)).unwrap();
+                grid.set(cell, mark);
+            }
+
+            assert!(grid.winner().is_none(), "someone won after X opened in cell {}", opening);
+        }
+    }
+
+    #[test]
+    fn every_opening_book_move_at_least_draws() {
+        for first_move in FirstMove::variants() {
+            for cell in book_moves(&Game::default(), Mark::X, first_move) {
+                assert!(first_move.allows(cell));
+                assert!(Game::default().with_move(cell, Mark::X).minimax(Mark::X) >= 0, "X opening at {:?}", cell);
+            }
+        }
+
+        // O's replies to each of X's openings (up to symmetry, so a corner, an edge, and the center)
+        for opening in Game::default().distinct_moves() {
+            let game = Game::default().with_move(opening, Mark::X);
+            let replies = book_moves(&game, Mark::O, FirstMove::AnyOpening);
+
+            assert!(!replies.is_empty());
+
+            for cell in replies {
+                assert!(game.get(cell).is_none());
+                assert!(game.with_move(cell, Mark::O).minimax(Mark::O) >= 0, "O reply at {:?} to {:?}", cell, opening);
+            }
+        }
+
+        // after that, the book has nothing to say
+        let game = Game::default().with_move(Cell::BottomLeft, Mark::X).with_move(Cell::MiddleMiddle, Mark::O);
+        assert!(book_moves(&game, Mark::X, FirstMove::AnyOpening).is_empty());
+    }
+
+    #[test]
+    fn hard_never_loses_without_the_center_bonus() {
+        let options = ComputerOptions { center_bonus: CenterBonus::NoCenterBonus, ..ComputerOptions::default() };
+
+        // the human tries every move at every turn, and Hard answers each one
+        fn play_out(game: Game, computer: Mark, options: ComputerOptions) {
+            if let Some((winner, _)) = game.winner() {
+                assert!(winner == computer, "Hard lost \"{}\"", game.transcript().unwrap());
+            }
+
+            if game.over() { return; }
+
+            if game.next_player() == computer {
+                let cell = generate_computer_input(&game, computer, Difficulty::Hard, options, 0, &mut GameRng::seeded(0)).unwrap();
+                play_out(game.with_move(cell, computer), computer, options);
+            } else {
+                for cell in Cell::variants().into_iter().filter(|cell| game.get(*cell).is_none()) {
+                    play_out(game.with_move(cell, game.next_player()), computer, options);
+                }
+            }
+        }
+
+        play_out(Game::default(), Mark::X, options);
+        play_out(Game::default(), Mark::O, options);
+    }
+}
\ No newline at end of file
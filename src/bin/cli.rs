@@ -0,0 +1,101 @@
+use std::io::{BufRead, Write};
+
+use rand::RngCore;
+use tic_tac_toe::ai::{ComputerOptions, generate_computer_input};
+use tic_tac_toe::board::{Cell, Game, Mark};
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::rng::GameRng;
+use tic_tac_toe::settings::Difficulty;
+
+// a minimal terminal frontend for tic-tac-toe, where the human plays X against the computer as O
+//
+//   cargo run --bin cli -- [easy|medium|hard]
+fn main() {
+    let difficulty = match std::env::args().nth(1).as_deref() {
+        None | Some("hard") => Difficulty::Hard,
+        Some("medium") => Difficulty::Medium,
+        Some("easy") => Difficulty::Easy,
+        Some(other) => {
+            eprintln!("unknown difficulty '{}', expected one of: easy, medium, hard", other);
+            std::process::exit(1);
+        }
+    };
+
+    let mut game = Game::default();
+    let mut rng = GameRng::default();
+    let game_seed = rng.next_u64();
+    let mut lines = std::io::stdin().lock().lines();
+
+    println!("You are X, playing against the computer on {} mode", difficulty);
+
+    while !game.over() {
+        let cell = match game.next_player() {
+            Mark::X => {
+                print_board(&game);
+                print!("Choose an empty cell (1-9): ");
+                std::io::stdout().flush().expect("unable to write to stdout");
+
+                let Some(Ok(line)) = lines.next() else { return; };
+
+                match parse_cell(&line) {
+                    Some(cell) if game.get(cell).is_none() => cell,
+                    Some(_) => {
+                        println!("That cell is already occupied");
+                        continue;
+                    }
+                    None => {
+                        println!("Please enter a number from 1 to 9");
+                        continue;
+                    }
+                }
+            }
+            Mark::O => {
+                let cell = generate_computer_input(&game, Mark::O, difficulty, ComputerOptions::default(), game_seed, &mut rng)
+                    .expect("the game is over when the board is full, so there should always be a legal move");
+                println!("The computer chooses {:?}", cell);
+                cell
+            }
+        };
+
+        game.set(cell, game.next_player());
+    }
+
+    print_board(&game);
+
+    match game.winner() {
+        None => println!("It's a tie!"),
+        Some((winner, _)) => println!("{} wins!", winner),
+    }
+}
+
+// cells are numbered left-to-right, top-to-bottom, like a phone keypad
+fn parse_cell(input: &str) -> Option<Cell> {
+    match input.trim().parse::<usize>() {
+        Ok(number @ 1..=9) => Some(Cell::variants()[number - 1]),
+        _ => None
+    }
+}
+
+// empty cells show their number, so the user knows what to type
+//
+//   X | 2 | 3
+//  ---+---+---
+//   4 | O | 6
+//  ---+---+---
+//   7 | 8 | 9
+fn print_board(game: &Game) {
+    println!();
+    for (row, marks) in game.board().into_iter().enumerate() {
+        if row > 0 { println!("---+---+---"); }
+
+        let symbols = marks.into_iter().enumerate()
+            .map(|(column, mark)| match mark {
+                Some(mark) => mark.to_string(),
+                None => (3 * row + column + 1).to_string(),
+            })
+            .collect::<Vec<String>>();
+
+        println!(" {} | {} | {}", symbols[0], symbols[1], symbols[2]);
+    }
+    println!();
+}
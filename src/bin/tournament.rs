@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use rand::RngCore;
+use tic_tac_toe::ai::{ComputerOptions, generate_computer_input};
+use tic_tac_toe::board::{Game, Mark};
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::rng::GameRng;
+use tic_tac_toe::settings::Difficulty;
+
+// a headless round-robin between every pair of difficulties, as a benchmark for the computer player
+// each pairing plays the same number of games as X and as O, so neither side benefits from moving first
+//
+//   cargo run --release --bin tournament -- [games per pairing] [seed]
+//
+// the same seed always produces the same results, so changes to the AI can be compared fairly
+//
+// every move is timed, too, and in release builds, if any one takes longer than MOVE_TIME_LIMIT, this exits with an
+// error, so that an accidental blowup in the AI's running time (e.g. a search which is no longer cut short) doesn't go
+// unnoticed (the slowest move is Hard's reply from the opening book, at around 200ms, or a few seconds in debug builds)
+const MOVE_TIME_LIMIT: Duration = Duration::from_secs(1);
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let games: usize = args.next().map(|arg| arg.parse().expect("games should be a number")).unwrap_or(1000);
+    let seed: u64 = args.next().map(|arg| arg.parse().expect("seed should be a number")).unwrap_or(0);
+
+    let mut rng = GameRng::seeded(seed);
+    let difficulties = Difficulty::variants();
+    let mut slowest = Duration::ZERO;
+
+    println!("{} games per pairing (seed {}), shown as win / draw / loss % for the row difficulty", games, seed);
+    println!();
+
+    print!("{:>10}", "");
+    for column in &difficulties {
+        print!(" | {:^17}", column.to_string());
+    }
+    println!();
+
+    for row in &difficulties {
+        print!("{:>10}", row.to_string());
+
+        for column in &difficulties {
+            let (mut wins, mut draws, mut losses) = (0, 0, 0);
+
+            for index in 0..games {
+                // alternate which difficulty plays X
+                let row_mark = if index % 2 == 0 { Mark::X } else { Mark::O };
+
+                match play(*row, *column, row_mark, &mut rng, &mut slowest) {
+                    Some(winner) if winner == row_mark => wins += 1,
+                    Some(_) => losses += 1,
+                    None => draws += 1,
+                }
+            }
+
+            let percent = |count: usize| 100.0 * count as f32 / games as f32;
+            print!(" | {:>5.1} {:>5.1} {:>5.1}", percent(wins), percent(draws), percent(losses));
+        }
+        println!();
+    }
+
+    println!();
+    println!("slowest move: {:.1}ms (limit {}ms, in release builds)", slowest.as_secs_f64() * 1000.0, MOVE_TIME_LIMIT.as_millis());
+
+    if !cfg!(debug_assertions) && slowest > MOVE_TIME_LIMIT {
+        eprintln!("the computer took too long to move");
+        std::process::exit(1);
+    }
+}
+
+// plays a single game to completion, returning the winning mark, if there is one
+// `slowest` is raised to the time taken by the slowest move in this game, if that's slower
+fn play(row: Difficulty, column: Difficulty, row_mark: Mark, rng: &mut GameRng, slowest: &mut Duration) -> Option<Mark> {
+    let mut game = Game::default();
+    let game_seed = rng.next_u64();
+
+    while !game.over() {
+        let mark = game.next_player();
+        let difficulty = if mark == row_mark { row } else { column };
+
+        let start = Instant::now();
+        let cell = generate_computer_input(&game, mark, difficulty, ComputerOptions::default(), game_seed, rng)
+            .expect("the game is over when the board is full, so there should always be a legal move");
+        *slowest = (*slowest).max(start.elapsed());
+
+        game.set(cell, mark);
+    }
+
+    game.winner().map(|(winner, _)| winner)
+}
@@ -0,0 +1,881 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Color, Component};
+use bevy::utils::HashMap;
+use macros::Dimension;
+
+use crate::Enumerated;
+use crate::settings::HumanMark;
+
+// rows and columns each have their own spacing (see the Dimension derive), so cells needn't be square
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Dimension, Component)]
+#[dimension(spacing = 250.0)]
+pub enum Row {
+    Bottom,
+    Middle,
+    Top,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Dimension, Component)]
+#[dimension(spacing = 250.0)]
+pub enum Column {
+    Left,
+    Middle,
+    Right
+}
+
+#[derive(Component, Enumerated, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Cell {
+    TopLeft,
+    TopMiddle,
+    TopRight,
+    MiddleLeft,
+    MiddleMiddle,
+    MiddleRight,
+    BottomLeft,
+    BottomMiddle,
+    BottomRight,
+}
+
+impl Cell {
+    pub fn row(&self) -> Row {
+        match self {
+            Cell::TopLeft => Row::Top,
+            Cell::TopMiddle => Row::Top,
+            Cell::TopRight => Row::Top,
+            Cell::MiddleLeft => Row::Middle,
+            Cell::MiddleMiddle => Row::Middle,
+            Cell::MiddleRight => Row::Middle,
+            Cell::BottomLeft => Row::Bottom,
+            Cell::BottomMiddle => Row::Bottom,
+            Cell::BottomRight => Row::Bottom,
+        }
+    }
+
+    pub fn column(&self) -> Column {
+        match self {
+            Cell::TopLeft => Column::Left,
+            Cell::TopMiddle => Column::Middle,
+            Cell::TopRight => Column::Right,
+            Cell::MiddleLeft => Column::Left,
+            Cell::MiddleMiddle => Column::Middle,
+            Cell::MiddleRight => Column::Right,
+            Cell::BottomLeft => Column::Left,
+            Cell::BottomMiddle => Column::Middle,
+            Cell::BottomRight => Column::Right,
+        }
+    }
+
+    pub fn from(row: Row, column: Column) -> Cell {
+        match row {
+            Row::Bottom => match column {
+                Column::Left => Cell::BottomLeft,
+                Column::Middle => Cell::BottomMiddle,
+                Column::Right => Cell::BottomRight,
+            }
+            Row::Middle => match column {
+                Column::Left => Cell::MiddleLeft,
+                Column::Middle => Cell::MiddleMiddle,
+                Column::Right => Cell::MiddleRight,
+            }
+            Row::Top => match column {
+                Column::Left => Cell::TopLeft,
+                Column::Middle => Cell::TopMiddle,
+                Column::Right => Cell::TopRight,
+            }
+        }
+    }
+
+    // where this cell is drawn in the board's CSS-style grid, as 1-based (row, column) lines, counting from the top-left
+    //
+    // Row::Top has position 1 (y points up, as in world coordinates, which Cell::hit() uses), but grid rows are counted
+    // downwards from the top, so the row position is negated: Top (1) => 1, Middle (0) => 2, Bottom (-1) => 3
+    // columns already run left to right, so Left (-1) => 1, Middle (0) => 2, Right (1) => 3
+    //
+    // so Cell::TopLeft is drawn at (1, 1), in the top-left, and Cell::BottomRight at (3, 3), in the bottom-right
+    pub fn grid_placement(&self) -> (i16, i16) {
+        ((2 - self.row().position()) as i16, (self.column().position() + 2) as i16)
+    }
+
+    // the cell's name, in chess-like algebraic notation: columns "a" to "c" (left to right), rows "1" to "3" (bottom to top)
+    // so the top-left cell is "a3" and the center is "b2"
+    pub fn name(&self) -> String {
+        let column = match self.column() {
+            Column::Left => 'a',
+            Column::Middle => 'b',
+            Column::Right => 'c',
+        };
+
+        let row = match self.row() {
+            Row::Bottom => '1',
+            Row::Middle => '2',
+            Row::Top => '3',
+        };
+
+        format!("{}{}", column, row)
+    }
+
+    // the inverse of name(), e.g. "b2" is the center
+    pub fn from_name(name: &str) -> Option<Cell> {
+        Cell::variants().into_iter().find(|cell| cell.name() == name)
+    }
+
+    // the cell's name in plain words, e.g. for the computer to say where it's moving
+    pub fn friendly_name(&self) -> &'static str {
+        match self {
+            Cell::TopLeft => "the top left corner",
+            Cell::TopMiddle => "the top edge",
+            Cell::TopRight => "the top right corner",
+            Cell::MiddleLeft => "the left edge",
+            Cell::MiddleMiddle => "the center",
+            Cell::MiddleRight => "the right edge",
+            Cell::BottomLeft => "the bottom left corner",
+            Cell::BottomMiddle => "the bottom edge",
+            Cell::BottomRight => "the bottom right corner",
+        }
+    }
+
+    pub fn is_corner(&self) -> bool {
+        *self == Self::TopLeft || *self == Self::TopRight || *self == Self::BottomLeft || *self == Self::BottomRight
+    }
+
+    // the cell opposite this one, reflected through the center (so the center is its own mirror image)
+    pub fn mirrored(&self) -> Cell {
+        Cell::at(-self.column().position(), -self.row().position())
+    }
+
+    // the cell at the given (column, row) positions, each of which is -1, 0, or 1
+    fn at(column: i8, row: i8) -> Cell {
+        Cell::from(Row::values()[(row + 1) as usize], Column::values()[(column + 1) as usize])
+    }
+
+    // the cell at a point on an unscaled board centered on the origin, which is Column::SPACING pixels across each column
+    // and Row::SPACING pixels up each row
+    pub fn hit(pos: Vec2) -> Option<Cell> {
+        match (Row::containing(pos.y), Column::containing(pos.x)) {
+            (None, _) | (_, None) => None,
+            (Some(row), Some(col)) => Some(Cell::from(row, col))
+        }
+    }
+}
+
+#[derive(Enumerated, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    BottomRow,
+    MiddleRow,
+    TopRow,
+    LeftColumn,
+    MiddleColumn,
+    RightColumn,
+    UpDiagonal,
+    DownDiagonal,
+}
+
+impl Line {
+    pub fn cells(&self) -> [Cell; 3] {
+        match self {
+            Self::BottomRow => [Cell::BottomLeft, Cell::BottomMiddle, Cell::BottomRight],
+            Self::MiddleRow => [Cell::MiddleLeft, Cell::MiddleMiddle, Cell::MiddleRight],
+            Self::TopRow => [Cell::TopLeft, Cell::TopMiddle, Cell::TopRight],
+            Self::LeftColumn => [Cell::TopLeft, Cell::MiddleLeft, Cell::BottomLeft],
+            Self::MiddleColumn => [Cell::TopMiddle, Cell::MiddleMiddle, Cell::BottomMiddle],
+            Self::RightColumn => [Cell::TopRight, Cell::MiddleRight, Cell::BottomRight],
+            Self::UpDiagonal => [Cell::BottomLeft, Cell::MiddleMiddle, Cell::TopRight],
+            Self::DownDiagonal => [Cell::TopLeft, Cell::MiddleMiddle, Cell::BottomRight],
+        }
+    }
+}
+
+#[derive(Component, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Mark {
+    #[default]
+    X,
+    O
+}
+
+impl std::fmt::Display for Mark {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mark::X => write!(f, "X"),
+            Mark::O => write!(f, "O"),
+        }
+    }
+}
+
+impl Mark {
+    pub fn color(&self) -> Color {
+        match self {
+            Mark::X => Color::RED,
+            Mark::O => Color::BLUE,
+        }
+    }
+
+    // HumanMark::HumanRandom is resolved to X or O before a game starts, so it's never either mark
+    pub fn is(&self, human_mark: HumanMark) -> bool {
+        match self {
+            Mark::X if human_mark == HumanMark::HumanX => true,
+            Mark::O if human_mark == HumanMark::HumanO => true,
+            _ => false
+        }
+    }
+}
+
+type Symmetry = fn(i8, i8) -> (i8, i8);
+
+// one mark made on the board, as recorded in a Game's log
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct MoveEvent {
+    pub cell: Cell,
+    pub mark: Mark,
+}
+
+// a Game is a log of MoveEvents, and everything else (the marks, the history, the winner) is folded from it
+// a move appends to the log, an undo pops it, and the board editor's set_unchecked() rewrites it, each refolding as needed
+// so a replay is a fold of the first few events, and the log is all there is to send to someone else
+//
+// All of Game's fields are private so that the folded state is always in step with the log
+// impl Default is required for impl Default on StateInfo
+#[derive(Default, Clone)]
+pub struct Game {
+    log: Vec<MoveEvent>,
+    marks: HashMap<Cell, Option<Mark>>,
+    history: Vec<Cell>,
+    // how many events at the start of the log were loaded (by from_notation() or the board editor) rather than played,
+    // and so are in no particular order
+    unordered: usize,
+    winner: Option<(Mark, Line)>,
+    over: bool
+}
+
+// two Games are equal if their boards are, however the marks got there
+// so "1. Xa1 Ob2 2. Xc3" and "1. Xc3 Ob2 2. Xa1" are the same position, even though their logs (and histories) differ
+impl PartialEq for Game {
+    fn eq(&self, other: &Game) -> bool {
+        Cell::variants().into_iter().all(|cell| self.get(cell) == other.get(cell))
+    }
+}
+
+impl Eq for Game {}
+
+impl Game {
+    // the game reached by making each move in the log, in order
+    pub fn from_log(log: &[MoveEvent]) -> Game {
+        let mut game = Game::default();
+
+        for event in log {
+            game.apply(*event);
+        }
+
+        game
+    }
+
+    // behind a getter so the user cannot rewrite the log directly
+    pub fn log(&self) -> &[MoveEvent] {
+        &self.log
+    }
+
+    // appends one event to the log, and folds it into the rest of the state
+    fn apply(&mut self, event: MoveEvent) {
+        self.log.push(event);
+        self.marks.insert(event.cell, Some(event.mark));
+        self.history.push(event.cell);
+        self.winner = Game::determine_winner(&self.marks);
+        self.over = self.winner.is_some() || self.marks.len() == 9;
+    }
+
+    // the order in which lines are checked for a winner (see determine_winner())
+    const WINNING_LINES: [Line; 8] = [
+        Line::TopRow,
+        Line::MiddleRow,
+        Line::BottomRow,
+        Line::LeftColumn,
+        Line::MiddleColumn,
+        Line::RightColumn,
+        Line::UpDiagonal,
+        Line::DownDiagonal,
+    ];
+
+    // the eight rotations and reflections of the board, each mapping a (column, row) position to another
+    const SYMMETRIES: [Symmetry; 8] = [
+        |x, y| (x, y),
+        |x, y| (-y, x),
+        |x, y| (-x, -y),
+        |x, y| (y, -x),
+        |x, y| (-x, y),
+        |x, y| (x, -y),
+        |x, y| (y, x),
+        |x, y| (-y, -x),
+    ];
+
+    // if the last move completed two lines at once, only the first one in WINNING_LINES is returned, i.e. rows
+    // (top to bottom) before columns (left to right) before diagonals (see completed_lines() for all of them)
+    //
+    // this runs after every move (and for every board the computer looks ahead to), so it only looks up the three
+    // cells of each line, without collecting anything along the way
+    fn determine_winner(marks: &HashMap<Cell, Option<Mark>>) -> Option<(Mark, Line)> {
+        let get = |cell: Cell| marks.get(&cell).copied().flatten();
+
+        Self::WINNING_LINES.into_iter().find_map(|line| {
+            let [a, b, c] = line.cells().map(get);
+            let mark = a?;
+            (b == Some(mark) && c == Some(mark)).then_some((mark, line))
+        })
+    }
+
+    // loads a board from a 9-character string, read left-to-right, top-to-bottom
+    // 'X' and 'O' are marks and '.' is an empty cell, so "XO..X...O" is the board
+    //
+    //   X | O |
+    //  ---+---+---
+    //     | X |
+    //  ---+---+---
+    //     |   | O
+    //
+    // X always moves first, so there must be exactly as many Xs as Os (X to move), or one more X (O to move)
+    // if the board already contains a completed line (or is full), the loaded game is over()
+    // the order in which the marks were made is unknown, so history() lists them top-to-bottom
+    pub fn from_notation(notation: &str) -> Result<Game, String> {
+        let chars = notation.chars().collect::<Vec<char>>();
+
+        if chars.len() != Cell::CARDINALITY {
+            return Err(format!("expected {} characters, but found {} in \"{}\"", Cell::CARDINALITY, chars.len(), notation))
+        }
+
+        let mut log = vec![];
+
+        for (cell, char) in Cell::variants().into_iter().zip(chars) {
+            let mark = match char {
+                'X' => Mark::X,
+                'O' => Mark::O,
+                '.' => continue,
+                other => return Err(format!("unexpected character '{}' in \"{}\"", other, notation))
+            };
+
+            log.push(MoveEvent { cell, mark });
+        }
+
+        // the position may already be decided, in which case it should behave exactly like a finished game
+        let mut game = Game::from_log(&log);
+        game.unordered = log.len();
+
+        let (xs, os) = (game.count(Mark::X), game.count(Mark::O));
+
+        if xs != os && xs != os + 1 {
+            return Err(format!("{} Xs and {} Os cannot occur in a real game in \"{}\"", xs, os, notation))
+        }
+
+        Ok(game)
+    }
+
+    // the inverse of from_notation(), e.g. "XO..X...O"
+    pub fn to_notation(&self) -> String {
+        Cell::variants().into_iter()
+            .map(|cell| match self.get(cell) {
+                Some(Mark::X) => 'X',
+                Some(Mark::O) => 'O',
+                None => '.',
+            })
+            .collect()
+    }
+
+    // the moves made so far, numbered like a chess game, with X's and O's moves paired up, e.g. "1. Xb2 Oa3 2. Xc1"
+    // a game loaded with from_notation() doesn't know the order its marks were made in, so it has no transcript (None)
+    pub fn transcript(&self) -> Option<String> {
+        if self.unordered > 0 { return None; }
+
+        let transcript = self.log.chunks(2).enumerate()
+            .map(|(turn, events)| {
+                let moves = events.iter()
+                    .map(|event| format!("{}{}", event.mark, event.cell.name()))
+                    .collect::<Vec<String>>();
+                format!("{}. {}", turn + 1, moves.join(" "))
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        Some(transcript)
+    }
+
+    // the inverse of transcript(), e.g. "1. Xb2 Oa3 2. Xc1", which has to be a game which could really have been played
+    pub fn from_transcript(transcript: &str) -> Result<Game, String> {
+        let mut game = Game::default();
+
+        // the move numbers ("1.", "2.", ...) are only there for people, so they're skipped
+        for word in transcript.split_whitespace().filter(|word| !word.ends_with('.')) {
+            let mark = game.next_player();
+
+            let Some(name) = word.strip_prefix(&mark.to_string()) else {
+                return Err(format!("expected a move by {}, but found \"{}\" in \"{}\"", mark, word, transcript))
+            };
+
+            let Some(cell) = Cell::from_name(name) else {
+                return Err(format!("unknown cell \"{}\" in \"{}\"", name, transcript))
+            };
+
+            if game.over() || game.get(cell).is_some() {
+                return Err(format!("\"{}\" is not a legal move in \"{}\"", word, transcript))
+            }
+
+            game.set(cell, mark);
+        }
+
+        Ok(game)
+    }
+
+    // a snapshot of the board, as rows from top to bottom, each listing its marks from left to right
+    // so board()[0][0] is the top-left cell, and board()[2][2] is the bottom-right cell
+    pub fn board(&self) -> [[Option<Mark>; 3]; 3] {
+        let mut board = [[None; 3]; 3];
+
+        for cell in Cell::variants() {
+            let (row, column) = cell.grid_placement();
+            board[row as usize - 1][column as usize - 1] = self.get(cell);
+        }
+
+        board
+    }
+
+    // how many cells are marked by `mark`
+    pub fn count(&self, mark: Mark) -> usize {
+        self.marks.values().filter(|m| **m == Some(mark)).count()
+    }
+
+    // whose turn it is on this board, given that X always moves first
+    pub fn next_player(&self) -> Mark {
+        match self.history.len() % 2 {
+            0 => Mark::X,
+            _ => Mark::O,
+        }
+    }
+
+    // scores this board from the point of view of `player`, assuming both sides play perfectly from here
+    //
+    //   +1 means `player` can force a win
+    //    0 means perfect play ends in a tie
+    //   -1 means `player` will lose against perfect play
+    pub fn minimax(&self, player: Mark) -> i8 {
+        match self.winner {
+            Some((winner, _)) if winner == player => return 1,
+            Some(_) => return -1,
+            None if self.over => return 0,
+            None => {}
+        }
+
+        let to_move = self.next_player();
+
+        let scores = Cell::variants().into_iter()
+            .filter(|cell| self.get(*cell).is_none())
+            .map(|cell| self.with_move(cell, to_move).minimax(player));
+
+        let best = if to_move == player { scores.max() } else { scores.min() };
+
+        best.unwrap_or(0)
+    }
+
+    // the empty cells, keeping only one of any cells which are equivalent by symmetry
+    // e.g. on an empty board there are just three distinct moves: a corner, an edge, and the center
+    pub fn distinct_moves(&self) -> Vec<Cell> {
+        let transform = |symmetry: Symmetry, cell: Cell| {
+            let (column, row) = symmetry(cell.column().position(), cell.row().position());
+            Cell::at(column, row)
+        };
+
+        // only the symmetries which leave the marks on the board where they are
+        let symmetries = Self::SYMMETRIES.into_iter()
+            .filter(|symmetry| Cell::variants().into_iter().all(|cell| self.get(cell) == self.get(transform(*symmetry, cell))))
+            .collect::<Vec<_>>();
+
+        let mut moves: Vec<Cell> = vec![];
+
+        for cell in Cell::variants().into_iter().filter(|cell| self.get(*cell).is_none()) {
+            if !moves.iter().any(|chosen| symmetries.iter().any(|symmetry| transform(*symmetry, *chosen) == cell)) {
+                moves.push(cell);
+            }
+        }
+
+        moves
+    }
+
+    // whether `mark` has two cells of this line, and the third is empty, so `mark` could complete it next turn
+    fn is_threat(&self, line: Line, mark: Mark) -> bool {
+        let marks = line.cells().map(|cell| self.get(cell));
+        marks.iter().filter(|m| **m == Some(mark)).count() == 2 && marks.contains(&None)
+    }
+
+    // empty cells which, if marked by `mark`, would create two (or more) threats at once, i.e. a fork
+    // the opponent can only block one of them, so (unless they can win first) `mark` wins on the following turn
+    pub fn forks(&self, mark: Mark) -> Vec<Cell> {
+        Cell::variants().into_iter()
+            .filter(|cell| self.get(*cell).is_none())
+            .filter(|cell| {
+                let next = self.with_move(*cell, mark);
+                let threats = Line::variants().into_iter()
+                    .filter(|line| line.cells().contains(cell) && next.is_threat(*line, mark))
+                    .count();
+                threats >= 2
+            })
+            .collect()
+    }
+
+    // every line held entirely by `mark`, where winner() only has the first one found
+    // a game normally ends at the first line, but the last move can complete two at once (e.g. through the center)
+    pub fn completed_lines(&self, mark: Mark) -> Vec<Line> {
+        Line::variants().into_iter()
+            .filter(|line| line.cells().iter().all(|cell| self.get(*cell) == Some(mark)))
+            .collect()
+    }
+
+    // behind a getter so the user cannot mutate this field directly
+    pub fn winner(&self) -> Option<(Mark, Line)> {
+        self.winner
+    }
+
+    // behind a getter so the user cannot mutate this field directly
+    pub fn over(&self) -> bool {
+        self.over
+    }
+
+    // behind a getter so the user cannot mutate the move history directly
+    // cells are listed in the order they were marked, so history()[0] is the first move
+    pub fn history(&self) -> &[Cell] {
+        &self.history
+    }
+
+    // how many cells have been marked, so 9 - move_count() are still empty
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    // how many moves undo() can take back: only those played since the game started or was loaded (see from_notation())
+    pub fn undoable_moves(&self) -> usize {
+        self.log.len() - self.unordered
+    }
+
+    // behind a getter so the user cannot access / mutate marks directly
+    pub fn get(&self, cell: Cell) -> Option<Mark> {
+        self.marks.get(&cell).cloned().flatten()
+    }
+
+    // a copy of this game with one more move made, leaving this game as it is (e.g. to look ahead)
+    pub fn with_move(&self, cell: Cell, mark: Mark) -> Game {
+        let mut next = self.clone();
+        next.set(cell, mark);
+        next
+    }
+
+    // empties the board, so the same Game can be played again (like replacing it with Game::default())
+    pub fn reset(&mut self) {
+        self.log.clear();
+        self.marks.clear();
+        self.history.clear();
+        self.unordered = 0;
+        self.winner = None;
+        self.over = false;
+    }
+
+    // only for the board editor (in debug builds): unlike set(), any cell can be changed to anything, even emptied, so the
+    // board might not be one which can occur in a real game (round-trip through from_notation() to check that it can)
+    //
+    // this is the one place the log isn't only appended to: any earlier event in the cell is dropped, and the rest refolded
+    // afterwards, the order of the whole log is unknown, just as if the board had been loaded with from_notation()
+    pub fn set_unchecked(&mut self, cell: Cell, mark: Option<Mark>) {
+        let mut log = std::mem::take(&mut self.log);
+        log.retain(|event| event.cell != cell);
+
+        if let Some(mark) = mark {
+            log.push(MoveEvent { cell, mark });
+        }
+
+        *self = Game::from_log(&log);
+        self.unordered = log.len();
+    }
+
+    // takes back the last move, returning the cell it was made in (or None, if there are no undoable_moves())
+    pub fn undo(&mut self) -> Option<Cell> {
+        if self.undoable_moves() == 0 { return None; }
+
+        let unordered = self.unordered;
+        let mut log = std::mem::take(&mut self.log);
+        let event = log.pop();
+        *self = Game::from_log(&log);
+        self.unordered = unordered;
+        event.map(|event| event.cell)
+    }
+
+    // behind a setter so we can recalculate the winner immediately
+    // callers check that the cell is empty first, so marking an occupied cell is a bug (caught in debug builds)
+    pub fn set(&mut self, cell: Cell, mark: Mark) {
+        debug_assert!(self.get(cell).is_none(), "{:?} is already marked", cell);
+        self.apply(MoveEvent { cell, mark });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn games_reaching_the_same_position_are_equal() {
+        let one = Game::from_transcript("1. Xa1 Ob2 2. Xc3").unwrap();
+        let other = Game::from_transcript("1. Xc3 Ob2 2. Xa1").unwrap();
+
+        assert!(one.history() != other.history());
+        assert!(one == other);
+        assert!(one == Game::from_notation(&one.to_notation()).unwrap());
+        assert!(one != Game::from_transcript("1. Xa1 Ob2").unwrap());
+    }
+
+    #[test]
+    fn folding_the_log_gives_the_same_game() {
+        // a win for X, a win for O, a tie, and a game still in progress
+        let transcripts = [
+            "1. Xa1 Ob2 2. Xa2 Oc3 3. Xa3",
+            "1. Xb2 Oa1 2. Xc3 Ob1 3. Xc2 Oc1",
+            "1. Xb2 Oa1 2. Xc3 Oa3 3. Xa2 Oc2 4. Xb3 Ob1 5. Xc1",
+            "1. Xb2 Oa1 2. Xc3",
+        ];
+
+        for transcript in transcripts {
+            let played = Game::from_transcript(transcript).unwrap();
+            let mut game = Game::default();
+
+            // after every move, the incremental state matches a fold of the log so far
+            for event in played.log() {
+                game.set(event.cell, event.mark);
+                let folded = Game::from_log(game.log());
+
+                assert!(folded.winner() == game.winner(), "different winners after \"{}\"", game.transcript().unwrap());
+                assert!(folded.over() == game.over(), "different over() after \"{}\"", game.transcript().unwrap());
+                assert!(folded == game);
+            }
+
+            // and undoing a move is the same as folding one event fewer
+            game.undo();
+            let folded = Game::from_log(&played.log()[..played.log().len() - 1]);
+            assert!(folded.winner() == game.winner() && folded.over() == game.over() && folded == game);
+        }
+    }
+
+    #[test]
+    fn a_last_move_can_complete_two_lines() {
+        // X's last move, in the top right, completes both the top row and the right column
+        let game = Game::from_transcript("1. Xa3 Oa1 2. Xb3 Ob1 3. Xc2 Ob2 4. Xc1 Oa2 5. Xc3").unwrap();
+
+        assert!(game.over());
+        assert!(game.winner() == Some((Mark::X, Line::TopRow)), "rows are checked before columns");
+        assert!(game.completed_lines(Mark::X) == vec![Line::TopRow, Line::RightColumn]);
+        assert!(game.completed_lines(Mark::O).is_empty());
+    }
+
+    #[test]
+    fn determine_winner_matches_checking_each_line() {
+        // the first line in WINNING_LINES whose three cells all hold the same mark
+        let naive = |game: &Game| Game::WINNING_LINES.into_iter().find_map(|line| {
+            [Mark::X, Mark::O].into_iter()
+                .find(|mark| line.cells().iter().all(|cell| game.get(*cell) == Some(*mark)))
+                .map(|mark| (mark, line))
+        });
+
+        let (mut full, mut undecided) = (0, 0);
+
+        // every board which from_notation() accepts, i.e. every 9-character string of 'X', 'O', and '.'
+        for index in 0..3usize.pow(9) {
+            let notation = (0..9).map(|place| ['.', 'X', 'O'][index / 3usize.pow(place) % 3]).collect::<String>();
+            let Ok(game) = Game::from_notation(&notation) else { continue };
+
+            assert!(game.winner() == naive(&game), "different winners for \"{}\"", notation);
+
+            if game.move_count() == 9 { full += 1; }
+            if game.winner().is_none() { undecided += 1; }
+        }
+
+        assert!(full > 0 && undecided > 0);
+    }
+
+    #[test]
+    fn a_decided_position_is_loaded_as_over() {
+        // X has the middle column
+        let won = Game::from_notation(".X.OXO.X.").unwrap();
+        assert!(won.over());
+        assert!(won.winner() == Some((Mark::X, Line::MiddleColumn)));
+
+        // a full board, with no line for either player
+        let tied = Game::from_notation("XOXXOOOXX").unwrap();
+        assert!(tied.over());
+        assert!(tied.winner().is_none());
+
+        let playing = Game::from_notation("X...O....").unwrap();
+        assert!(!playing.over());
+    }
+
+    #[test]
+    fn with_move_leaves_the_original_game_as_it_is() {
+        let game = Game::from_transcript("1. Xb2 Oa1").unwrap();
+        let next = game.with_move(Cell::TopRight, Mark::X);
+
+        assert!(next.get(Cell::TopRight) == Some(Mark::X));
+        assert!(game.get(Cell::TopRight).is_none());
+        assert_eq!(game.history(), [Cell::MiddleMiddle, Cell::BottomLeft]);
+        assert_eq!(game.log().len(), 2);
+        assert!(game == Game::from_transcript("1. Xb2 Oa1").unwrap());
+    }
+
+    #[test]
+    fn the_double_corner_fork() {
+        // X has opposite corners, and O has the center and an edge, so X's bottom-left corner threatens both the left
+        // column and the bottom row at once
+        let game = Game::from_notation("XO..O...X").unwrap();
+
+        assert_eq!(game.forks(Mark::X), vec![Cell::BottomLeft]);
+        assert!(game.forks(Mark::O).is_empty());
+    }
+
+    #[test]
+    fn count_marks() {
+        let empty = Game::default();
+        assert_eq!((empty.count(Mark::X), empty.count(Mark::O)), (0, 0));
+
+        let partial = Game::from_notation("XO..X...O").unwrap();
+        assert_eq!((partial.count(Mark::X), partial.count(Mark::O)), (2, 2));
+
+        let full = Game::from_notation("XOXXOOOXX").unwrap();
+        assert_eq!((full.count(Mark::X), full.count(Mark::O)), (5, 4));
+    }
+
+    #[test]
+    fn grid_placement_runs_from_the_top_left() {
+        assert_eq!(Cell::TopLeft.grid_placement(), (1, 1));
+        assert_eq!(Cell::TopRight.grid_placement(), (1, 3));
+        assert_eq!(Cell::MiddleMiddle.grid_placement(), (2, 2));
+        assert_eq!(Cell::BottomLeft.grid_placement(), (3, 1));
+        assert_eq!(Cell::BottomRight.grid_placement(), (3, 3));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "already marked")]
+    fn marking_an_occupied_cell_panics_in_debug_builds() {
+        let mut game = Game::from_transcript("1. Xb2").unwrap();
+        game.set(Cell::MiddleMiddle, Mark::O);
+    }
+
+    #[test]
+    fn a_reset_game_is_a_fresh_game() {
+        let mut game = Game::from_transcript("1. Xa1 Ob2 2. Xa2 Oc3 3. Xa3").unwrap();
+        game.reset();
+
+        let fresh = Game::default();
+
+        assert!(game == fresh);
+        assert!(game.log().is_empty() && game.history().is_empty());
+        assert!(game.winner() == fresh.winner() && game.over() == fresh.over());
+        assert!(game.next_player() == Mark::X);
+    }
+
+    #[test]
+    fn board_matches_get() {
+        let game = Game::from_notation("XO..X...O").unwrap();
+        let board = game.board();
+
+        for cell in Cell::variants() {
+            let (row, column) = cell.grid_placement();
+            assert!(board[row as usize - 1][column as usize - 1] == game.get(cell), "{:?} is different", cell);
+        }
+
+        assert!(board[0] == [Some(Mark::X), Some(Mark::O), None]);
+        assert!(board[2] == [None, None, Some(Mark::O)]);
+    }
+
+    #[test]
+    fn completed_lines_lists_every_line() {
+        // none yet, then X's middle column, then X's top row and right column together
+        let none = Game::from_notation("XO..X...O").unwrap();
+        let one = Game::from_notation(".X.OXO.X.").unwrap();
+        let two = Game::from_notation("XXXOOXOOX").unwrap();
+
+        assert!(none.completed_lines(Mark::X).is_empty());
+        assert!(one.completed_lines(Mark::X) == vec![Line::MiddleColumn]);
+        assert!(two.completed_lines(Mark::X) == vec![Line::TopRow, Line::RightColumn]);
+        assert!(two.completed_lines(Mark::O).is_empty());
+    }
+
+    #[test]
+    fn a_loaded_board_has_no_transcript() {
+        // the marks are in cell order here, which is not an order they could have been made in
+        let mut game = Game::from_notation("XX.OO....").unwrap();
+        assert_eq!(game.transcript(), None);
+
+        // nor is it known once more moves are made, as they'd be numbered from the wrong move
+        game.set(Cell::TopRight, Mark::X);
+        assert_eq!(game.transcript(), None);
+
+        let played = Game::from_transcript("1. Xa3 Oa1 2. Xb3 Ob1").unwrap();
+        assert_eq!(played.transcript().as_deref(), Some("1. Xa3 Oa1 2. Xb3 Ob1"));
+    }
+
+    #[test]
+    fn undo_stops_at_a_loaded_board() {
+        // O's mark wasn't necessarily made last, so taking back the X would leave a board no real game could reach
+        let mut game = Game::from_notation("O...X....").unwrap();
+        assert_eq!(game.undoable_moves(), 0);
+        assert_eq!(game.undo(), None);
+        assert!(game == Game::from_notation("O...X....").unwrap());
+
+        // moves made after loading it can still be taken back, but no further
+        game.set(Cell::TopRight, Mark::X);
+        game.set(Cell::BottomRight, Mark::O);
+        assert_eq!(game.undoable_moves(), 2);
+        assert_eq!(game.undo(), Some(Cell::BottomRight));
+        assert_eq!(game.undo(), Some(Cell::TopRight));
+        assert_eq!(game.undo(), None);
+        assert!(game.next_player() == Mark::X);
+    }
+
+    // the rows and columns of a board whose cells are twice as wide as they are tall
+    #[derive(Debug, PartialEq, Clone, Copy, Dimension)]
+    #[dimension(spacing = 100.0)]
+    enum ShortRow {
+        Bottom,
+        Middle,
+        Top,
+    }
+
+    #[derive(Debug, PartialEq, Clone, Copy, Dimension)]
+    #[dimension(spacing = 200.0)]
+    enum WideColumn {
+        Left,
+        Middle,
+        Right,
+    }
+
+    #[test]
+    fn clicks_on_a_non_square_board_find_the_right_cell() {
+        assert_eq!((ShortRow::SPACING, WideColumn::SPACING), (100.0, 200.0));
+
+        // each row is 100 pixels tall, from -150 to 150, and each column is 200 pixels wide, from -300 to 300
+        assert_eq!(ShortRow::containing(-120.0), Some(ShortRow::Bottom));
+        assert_eq!(ShortRow::containing(49.0), Some(ShortRow::Middle));
+        assert_eq!(ShortRow::containing(120.0), Some(ShortRow::Top));
+        assert_eq!(ShortRow::containing(160.0), None);
+
+        assert_eq!(WideColumn::containing(-250.0), Some(WideColumn::Left));
+        assert_eq!(WideColumn::containing(99.0), Some(WideColumn::Middle));
+        assert_eq!(WideColumn::containing(250.0), Some(WideColumn::Right));
+        assert_eq!(WideColumn::containing(310.0), None);
+
+        // so a click 90 pixels right and up is in the top row, but the middle column, whereas on a square board with
+        // 100 pixel cells it would have been in the top right
+        assert_eq!((ShortRow::containing(90.0), WideColumn::containing(90.0)), (Some(ShortRow::Top), Some(WideColumn::Middle)));
+    }
+
+    #[test]
+    fn hit_uses_each_dimension_spacing() {
+        let (width, height) = (Column::SPACING, Row::SPACING);
+
+        assert_eq!(Cell::hit(Vec2::ZERO), Some(Cell::MiddleMiddle));
+        assert_eq!(Cell::hit(Vec2::new(width, height)), Some(Cell::TopRight));
+        assert_eq!(Cell::hit(Vec2::new(-width, -height)), Some(Cell::BottomLeft));
+        assert_eq!(Cell::hit(Vec2::new(1.4 * width, 0.0)), Some(Cell::MiddleRight));
+        assert_eq!(Cell::hit(Vec2::new(1.6 * width, 0.0)), None);
+        assert_eq!(Cell::hit(Vec2::new(0.0, -1.6 * height)), None);
+    }
+}
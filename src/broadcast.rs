@@ -0,0 +1,238 @@
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use bevy::log::info;
+
+use crate::board::{Cell, Game, Mark};
+
+// watching a game over the network: whoever is playing hosts it, and anyone who connects to them spectates it, seeing
+// each move as it's made, without being able to make any of their own
+//
+// everything is sent one way, from the host to its spectators, as lines of text, e.g.
+//
+//   board X...O....
+//   move Xc1
+//   move Oa3
+//
+// a spectator is sent the whole board (see Game::to_notation()) when they first connect, and again whenever it changes
+// by anything other than a move (an undo, or a new game), and otherwise just each move as it's made, as in a transcript
+
+// the port games are hosted on, unless a spectator asks for another
+pub const PORT: u16 = 7878;
+
+// how long a spectator waits for a host to answer, before giving up on it
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// how long the host waits for a spectator to take a message, before giving up on them, so one slow spectator can't
+// hold up the game
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// one line sent from the host to its spectators
+#[derive(Clone, PartialEq, Eq)]
+pub enum Broadcast {
+    Board(Game),
+    Move(Mark, Cell),
+}
+
+impl Broadcast {
+    pub fn to_line(&self) -> String {
+        match self {
+            Broadcast::Board(game) => format!("board {}\n", game.to_notation()),
+            Broadcast::Move(mark, cell) => format!("move {}{}\n", mark, cell.name()),
+        }
+    }
+
+    // a line which can't be understood is None, and is skipped, so a newer host can send more than this understands
+    pub fn from_line(line: &str) -> Option<Broadcast> {
+        let (kind, value) = line.trim_end().split_once(' ')?;
+
+        match kind {
+            "board" => Game::from_notation(value).ok().map(Broadcast::Board),
+            "move" => {
+                let mark = [Mark::X, Mark::O].into_iter().find(|mark| value.starts_with(&mark.to_string()))?;
+                Some(Broadcast::Move(mark, Cell::from_name(&value[1..])?))
+            }
+            _ => None,
+        }
+    }
+}
+
+// the host's end, which lets spectators in, and tells them about every change to the game
+pub struct Host {
+    listener: TcpListener,
+    spectators: Vec<TcpStream>,
+    sent: Game, // the game as the spectators have last been told about it
+}
+
+impl Host {
+    // listens for spectators on every network interface, on the given port (or any free one, for 0, see address())
+    pub fn bind(port: u16) -> io::Result<Host> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Host { listener, spectators: vec![], sent: Game::default() })
+    }
+
+    pub fn address(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn spectators(&self) -> usize {
+        self.spectators.len()
+    }
+
+    // lets in anyone who has connected since the last update, and tells every spectator what's happened to the game
+    // since then, dropping any who have gone away
+    pub fn update(&mut self, game: &Game) {
+        let changes = self.changes(game);
+
+        self.spectators.retain_mut(|stream| changes.iter().all(|change| send(stream, change).is_ok()));
+
+        while let Ok((mut stream, address)) = self.listener.accept() {
+            let joined = stream.set_nonblocking(false)
+                .and_then(|_| stream.set_write_timeout(Some(WRITE_TIMEOUT)))
+                .and_then(|_| send(&mut stream, &Broadcast::Board(game.clone())));
+
+            if joined.is_ok() {
+                info!("{} is spectating", address);
+                self.spectators.push(stream);
+            }
+        }
+
+        self.sent = game.clone();
+    }
+
+    // just the new moves, if that's all that's changed, and otherwise, the whole board
+    fn changes(&self, game: &Game) -> Vec<Broadcast> {
+        let (sent, log) = (self.sent.log(), game.log());
+
+        if log.starts_with(sent) {
+            log[sent.len()..].iter().map(|event| Broadcast::Move(event.mark, event.cell)).collect()
+        } else {
+            vec![Broadcast::Board(game.clone())]
+        }
+    }
+}
+
+fn send(stream: &mut TcpStream, broadcast: &Broadcast) -> io::Result<()> {
+    stream.write_all(broadcast.to_line().as_bytes())
+}
+
+// the spectator's end, which keeps its own copy of the host's game up to date
+pub struct Spectator {
+    reader: BufReader<TcpStream>,
+    line: String, // whatever's been received of the next line, until all of it has
+    game: Game,
+}
+
+impl Spectator {
+    // the address is a host name or IP address, with or without a port (which is otherwise PORT), e.g. "192.168.1.20"
+    pub fn connect(address: &str) -> io::Result<Spectator> {
+        let address = if address.contains(':') { address.to_string() } else { format!("{}:{}", address, PORT) };
+
+        let Some(socket) = address.to_socket_addrs()?.next() else {
+            return Err(io::Error::new(ErrorKind::NotFound, format!("could not find \"{}\"", address)));
+        };
+
+        let stream = TcpStream::connect_timeout(&socket, CONNECT_TIMEOUT)?;
+        stream.set_nonblocking(true)?;
+
+        Ok(Spectator { reader: BufReader::new(stream), line: String::new(), game: Game::default() })
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    // catches up with everything the host has sent since the last call, without waiting for anything more, returning
+    // whether the game has changed, or an error once the host has gone (which is how a hosted game ends)
+    //
+    // a move which can't be made on this copy of the game is skipped, as the next board will put it right
+    pub fn receive(&mut self) -> io::Result<bool> {
+        let mut changed = false;
+
+        loop {
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return Err(io::Error::new(ErrorKind::UnexpectedEof, "the host has left")),
+                Ok(_) if !self.line.ends_with('\n') => continue,
+                Ok(_) => {
+                    match Broadcast::from_line(&self.line) {
+                        Some(Broadcast::Board(game)) => {
+                            self.game = game;
+                            changed = true;
+                        }
+                        Some(Broadcast::Move(mark, cell)) if !self.game.over() && self.game.get(cell).is_none() => {
+                            self.game.set(cell, mark);
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+
+                    self.line.clear();
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(changed),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    // waits (for a while) for the spectator to catch up with the host's game
+    fn catch_up(spectator: &mut Spectator, game: &Game) -> bool {
+        let start = Instant::now();
+
+        while start.elapsed() < Duration::from_secs(5) {
+            if spectator.receive().is_ok() && spectator.game() == game {
+                return true;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        false
+    }
+
+    #[test]
+    fn broadcasts_read_back_as_they_were_written() {
+        let board = Broadcast::Board(Game::from_notation("X...O...X").unwrap());
+        let mv = Broadcast::Move(Mark::O, Cell::TopRight);
+
+        assert!(Broadcast::from_line(&board.to_line()) == Some(board));
+        assert!(Broadcast::from_line(&mv.to_line()) == Some(mv));
+        assert!(Broadcast::from_line("move Xd4\n").is_none());
+        assert!(Broadcast::from_line("chat hello\n").is_none());
+    }
+
+    #[test]
+    fn a_spectator_follows_the_host_until_it_leaves() {
+        let mut host = Host::bind(0).unwrap();
+        let port = host.address().unwrap().port();
+        let mut spectator = Spectator::connect(&format!("127.0.0.1:{}", port)).unwrap();
+
+        // joining partway through a game
+        let mut game = Game::from_transcript("1. Xb2 Oa3").unwrap();
+        host.update(&game);
+        assert!(host.spectators() == 1);
+        assert!(catch_up(&mut spectator, &game));
+
+        game.set(Cell::BottomRight, Mark::X);
+        host.update(&game);
+        assert!(catch_up(&mut spectator, &game));
+
+        game.undo();
+        game.undo();
+        host.update(&game);
+        assert!(catch_up(&mut spectator, &game));
+
+        drop(host);
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(spectator.receive().is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::board::{Cell, Game, Mark};
+use tic_tac_toe::settings::GameMode;
+
+use crate::{AppState, clear_entities_with_state, draw_screen};
+use crate::menu::{button, hover_button, text_button};
+
+// a debug-only screen for building any position, cell by cell, e.g. to try out the computer, or to write a new puzzle
+// it can only be opened from the menu in debug builds, so players never see it
+//
+// clicking a cell changes it from empty, to X, to O, and back to empty again, then either "One Player" or "Two Players"
+// starts a game from the position on the board (as long as it's one which can occur in a real game)
+pub fn plugin(app: &mut App) {
+    app
+        .insert_resource(EditedPosition::default())
+        .add_systems(OnEnter(AppState::Editor), setup)
+        .add_systems(Update, edit_cell.run_if(in_state(AppState::Editor)))
+        .add_systems(Update, show_position.run_if(in_state(AppState::Editor)))
+        .add_systems(Update, hover_button::<EditorCell>.run_if(in_state(AppState::Editor)))
+        .add_systems(Update, hover_button::<GameMode>.run_if(in_state(AppState::Editor)))
+        .add_systems(Update, hover_button::<BackToMenu>.run_if(in_state(AppState::Editor)))
+        .add_systems(Update, play_from_here.run_if(in_state(AppState::Editor)))
+        .add_systems(Update, back_to_menu.run_if(in_state(AppState::Editor)))
+        .add_systems(OnExit(AppState::Editor), clear_entities_with_state(AppState::Editor));
+}
+
+// the board being edited, and then (once a game is started from it) the position the game screen starts from
+// the game screen takes the position when it starts, so any later game starts from an empty board again
+#[derive(Resource, Default)]
+pub struct EditedPosition {
+    pub board: Game,
+    pub play: bool,
+}
+
+#[derive(Component)]
+struct EditorCell(Cell);
+
+// tags the text showing whose turn it is, who won, or what's wrong with the position
+#[derive(Component)]
+struct PositionStatus;
+
+#[derive(Component)]
+struct BackToMenu;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/larabie.otf");
+
+    draw_screen(&mut commands, AppState::Editor).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "board editor",
+                    TextStyle { font: font.clone(), font_size: 60.0, color: Color::BLACK }
+                ));
+
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_template_rows: vec![GridTrack::px(120.0); 3],
+                        grid_template_columns: vec![GridTrack::px(120.0); 3],
+                        ..default()
+                    },
+                    ..default()
+                }).with_children(|parent| {
+                    for cell in Cell::variants() {
+                        let (row, column) = cell.grid_placement();
+
+                        parent.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    grid_row: GridPlacement::start(row),
+                                    grid_column: GridPlacement::start(column),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    border: UiRect::all(Val::Px(2.0)),
+                                    ..default()
+                                },
+                                background_color: Color::NONE.into(),
+                                ..default()
+                            },
+                            EditorCell(cell)
+                        )).with_children(|parent| {
+                            parent.spawn(TextBundle::from_section("", TextStyle { font: font.clone(), font_size: 90.0, color: Color::BLACK }));
+                        });
+                    }
+                });
+
+                parent.spawn((
+                    TextBundle::from_section("", TextStyle { font: font.clone(), font_size: 30.0, color: Color::BLACK }),
+                    PositionStatus
+                ));
+
+                parent.spawn(NodeBundle {
+                    style: Style { column_gap: Val::Px(20.0), ..default() },
+                    ..default()
+                }).with_children(|parent| {
+                    button(GameMode::OnePlayer, parent, font.clone(), 40.0);
+                    button(GameMode::TwoPlayers, parent, font.clone(), 40.0);
+                });
+
+                text_button(parent, "back", BackToMenu, font.clone(), 30.0);
+            });
+    });
+}
+
+fn edit_cell(
+    buttons: Query<(&Interaction, &EditorCell), Changed<Interaction>>,
+    mut position: ResMut<EditedPosition>,
+) {
+    for (interaction, EditorCell(cell)) in &buttons {
+        if *interaction != Interaction::Pressed { continue; }
+
+        let next = match position.board.get(*cell) {
+            None => Some(Mark::X),
+            Some(Mark::X) => Some(Mark::O),
+            Some(Mark::O) => None,
+        };
+
+        position.board.set_unchecked(*cell, next);
+    }
+}
+
+// whether the board can be played from, and if so, whose turn it is, or how it ended
+fn status(board: &Game) -> String {
+    match Game::from_notation(&board.to_notation()) {
+        Err(_) => format!("{} Xs and {} Os can't happen in a real game", board.count(Mark::X), board.count(Mark::O)),
+        Ok(game) => match game.winner() {
+            Some((winner, _)) => format!("{} has won", winner),
+            None if game.over() => "it's a tie".to_string(),
+            None => format!("{} to move", game.next_player()),
+        }
+    }
+}
+
+fn show_position(
+    position: Res<EditedPosition>,
+    cells: Query<(&EditorCell, &Children)>,
+    mut texts: Query<&mut Text, Without<PositionStatus>>,
+    mut statuses: Query<&mut Text, With<PositionStatus>>,
+) {
+    if !position.is_changed() { return; }
+
+    for (EditorCell(cell), children) in &cells {
+        let mut iter = texts.iter_many_mut(children);
+
+        while let Some(mut text) = iter.fetch_next() {
+            let mark = position.board.get(*cell);
+            text.sections[0].value = mark.map(|mark| mark.to_string()).unwrap_or_default();
+            text.sections[0].style.color = mark.map(|mark| mark.color()).unwrap_or(Color::BLACK);
+        }
+    }
+
+    for mut text in statuses.iter_mut() {
+        text.sections[0].value = status(&position.board);
+    }
+}
+
+fn play_from_here(
+    buttons: Query<(&Interaction, &GameMode), Changed<Interaction>>,
+    mut position: ResMut<EditedPosition>,
+    mut game_mode: ResMut<GameMode>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mode) in &buttons {
+        if *interaction != Interaction::Pressed { continue; }
+
+        // the marks are given an order which can occur in a real game, so the game screen knows whose turn it is
+        let Ok(game) = Game::from_notation(&position.board.to_notation()) else { continue; };
+
+        *position = EditedPosition { board: game, play: true };
+        *game_mode = *mode;
+        app_state.set(AppState::Game);
+    }
+}
+
+fn back_to_menu(
+    query: Query<&Interaction, (Changed<Interaction>, With<BackToMenu>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Menu)
+        }
+    }
+}
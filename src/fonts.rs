@@ -0,0 +1,80 @@
+use ab_glyph::Font as _;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+// larabie.otf only has a limited set of characters, and anything else (accented letters, emoji, ...) would be drawn
+// as an empty box, so text drawn in larabie is split into runs, with any missing characters drawn in DejaVu Sans
+pub fn plugin(app: &mut App) {
+    app
+        .add_systems(Startup, load_fonts)
+        .add_systems(PostUpdate, fall_back_on_missing_glyphs.before(UiSystem::Layout));
+}
+
+#[derive(Resource)]
+struct FontChain {
+    primary: Handle<Font>,
+    fallback: Handle<Font>,
+}
+
+fn load_fonts(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(FontChain {
+        primary: asset_server.load("fonts/larabie.otf"),
+        fallback: asset_server.load("fonts/DejaVuSans.ttf"),
+    });
+}
+
+fn fall_back_on_missing_glyphs(
+    chain: Res<FontChain>,
+    fonts: Res<Assets<Font>>,
+    mut loaded: EventReader<AssetEvent<Font>>,
+    mut texts: Query<&mut Text>,
+) {
+    // glyphs can't be checked until the font has loaded, so then check all of the text that was drawn before that
+    let primary_just_loaded = loaded.read().any(|event| event.is_loaded_with_dependencies(&chain.primary));
+
+    let Some(primary) = fonts.get(&chain.primary) else { return; };
+
+    let has_glyph = |c: char| c.is_whitespace() || primary.font.glyph_id(c).0 != 0;
+
+    for mut text in texts.iter_mut() {
+        if !primary_just_loaded && !text.is_changed() { continue; }
+
+        let missing = text.sections.iter()
+            .any(|section| section.style.font == chain.primary && !section.value.chars().all(has_glyph));
+
+        // only touch the text if something needs to change, as that marks it as changed again
+        if !missing { continue; }
+
+        let mut sections = vec![];
+
+        for section in text.sections.drain(..) {
+            if section.style.font != chain.primary {
+                sections.push(section);
+                continue;
+            }
+
+            // split into runs of characters which either are, or aren't, in the primary font
+            let mut run = String::new();
+            let mut run_has_glyphs = true;
+
+            for c in section.value.chars() {
+                if has_glyph(c) != run_has_glyphs && !run.is_empty() {
+                    sections.push(run_section(&mut run, run_has_glyphs, &section.style, &chain));
+                }
+                run_has_glyphs = has_glyph(c);
+                run.push(c);
+            }
+
+            if !run.is_empty() {
+                sections.push(run_section(&mut run, run_has_glyphs, &section.style, &chain));
+            }
+        }
+
+        text.sections = sections;
+    }
+}
+
+fn run_section(run: &mut String, has_glyphs: bool, style: &TextStyle, chain: &FontChain) -> TextSection {
+    let font = if has_glyphs { chain.primary.clone() } else { chain.fallback.clone() };
+    TextSection::new(std::mem::take(run), TextStyle { font, ..style.clone() })
+}
\ No newline at end of file
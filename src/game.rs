@@ -1,12 +1,31 @@
 use std::time::Duration;
 
-use bevy::ecs::system::EntityCommands;
+use bevy::ecs::schedule::StateTransitionEvent;
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::prelude::*;
-use macros::Dimension;
-use rand::prelude::*;
-
-use crate::{AppState, clear_entities, draw_screen, Enumerated};
-use crate::settings::{Difficulty, GameMode, HumanMark};
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::ui::FocusPolicy;
+use bevy::window::{PrimaryWindow, RequestRedraw, WindowFocused, WindowResized};
+use rand::{Rng, RngCore};
+use rand::seq::SliceRandom;
+use tic_tac_toe::ai::{ComputerOptions, FORGIVE_AFTER, explain_move, forgive, generate_computer_input, rank_cells, recorded_move};
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::board::{Cell, Column, Game, Line, Mark, Row};
+use tic_tac_toe::broadcast::{Host, PORT};
+use tic_tac_toe::recording::Recording;
+use tic_tac_toe::rng::GameRng;
+use tic_tac_toe::snapshot::GameSnapshot;
+use tic_tac_toe::stats::{GameResult, Skill, Stats, NEVER_GIVE_UP};
+use tic_tac_toe::settings::{AutoDifficulty, AutoRestart, BoardOutline, BoardShake, CellsLeft, CenterBonus, Countdown, Difficulty, FirstMove, Forgiveness, GameMode, GameSpeed, GridColor, Hosting, HumanMark, LineHints, MarkShading, MatchLength, MoveAnnouncement, MoveNumbers, OccupiedCell, Overtime, PlaceOn, PlacementPitch, PlayStyle, PowerSaving, ResultEmoji, ResultOverlay, ResumeCue, Ripple, SideSwap, Sound, TapToConfirm, Teaching, Theme, ThreatPriority, TieBreak, TieColor, TurnPrompt, WinningLine};
+use tic_tac_toe::theme_file::ThemeFiles;
+
+use crate::{AppState, Dismissable, ModalWindow, clear_entities, draw_screen, spawn_modal};
+use crate::help::{HelpOverlay, help_hidden};
+use crate::editor::EditedPosition;
+use crate::puzzles::{Goal, PUZZLES, SelectedPuzzle};
+use crate::scores::STATS_FILE;
 
 #[derive(States, Clone, Hash, PartialEq, Eq, Debug, Default)]
 enum GameState {
@@ -14,465 +33,2222 @@ enum GameState {
     GameNotInProgress,
     XTurn,
     OTurn,
+    Overtime,
+    InstantReplay,
     GameOver
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Dimension, Component)]
-enum Row {
-    Bottom,
-    Middle,
-    Top,
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Dimension, Component)]
-enum Column {
-    Left,
-    Middle,
-    Right
-}
-
-#[derive(Component, Enumerated, PartialEq, Eq, Hash, Clone, Copy, Debug)]
-enum Cell {
-    TopLeft,
-    TopMiddle,
-    TopRight,
-    MiddleLeft,
-    MiddleMiddle,
-    MiddleRight,
-    BottomLeft,
-    BottomMiddle,
-    BottomRight,
-}
-
-impl Cell {
-    fn row(&self) -> Row {
-        match self {
-            Cell::TopLeft => Row::Top,
-            Cell::TopMiddle => Row::Top,
-            Cell::TopRight => Row::Top,
-            Cell::MiddleLeft => Row::Middle,
-            Cell::MiddleMiddle => Row::Middle,
-            Cell::MiddleRight => Row::Middle,
-            Cell::BottomLeft => Row::Bottom,
-            Cell::BottomMiddle => Row::Bottom,
-            Cell::BottomRight => Row::Bottom,
-        }
-    }
-
-    fn column(&self) -> Column {
-        match self {
-            Cell::TopLeft => Column::Left,
-            Cell::TopMiddle => Column::Middle,
-            Cell::TopRight => Column::Right,
-            Cell::MiddleLeft => Column::Left,
-            Cell::MiddleMiddle => Column::Middle,
-            Cell::MiddleRight => Column::Right,
-            Cell::BottomLeft => Column::Left,
-            Cell::BottomMiddle => Column::Middle,
-            Cell::BottomRight => Column::Right,
-        }
-    }
-
-    fn from(row: Row, column: Column) -> Cell {
-        match row {
-            Row::Bottom => match column {
-                Column::Left => Cell::BottomLeft,
-                Column::Middle => Cell::BottomMiddle,
-                Column::Right => Cell::BottomRight,
-            }
-            Row::Middle => match column {
-                Column::Left => Cell::MiddleLeft,
-                Column::Middle => Cell::MiddleMiddle,
-                Column::Right => Cell::MiddleRight,
-            }
-            Row::Top => match column {
-                Column::Left => Cell::TopLeft,
-                Column::Middle => Cell::TopMiddle,
-                Column::Right => Cell::TopRight,
-            }
+impl GameState {
+    // every change of GameState which the game should ever make (checked in debug builds, see check_transitions())
+    fn can_become(&self, next: &GameState) -> bool {
+        match (self, next) {
+            // a new game (even one loaded from a position which is already decided, see capture_input())
+            (GameState::GameNotInProgress, GameState::XTurn | GameState::OTurn) => true,
+            // a move, or a move taken back (so it can be the same player's turn again), or the end of the game
+            (GameState::XTurn | GameState::OTurn, _) => *next != GameState::GameNotInProgress,
+            (GameState::Overtime, GameState::XTurn) => true,
+            (GameState::InstantReplay, GameState::GameOver) => true,
+            // "play again", or leaving the game screen
+            (GameState::GameOver, GameState::XTurn | GameState::GameNotInProgress) => true,
+            _ => false,
+        }
+    }
+}
+
+// anything drawn on the board during a game, which is cleared away along with the marks (e.g. when a move is undone)
+type OnBoard = Or<(With<Mark>, With<MoveNumber>, With<Preview>)>;
+
+// how much bigger or smaller the board is drawn than on the default 800x800 window
+// the board keeps its shape, centered, as the window is resized, so this is based on whichever side of the window is
+// the tighter fit (see rescale_board())
+#[derive(Resource)]
+struct BoardScale(f32);
+
+impl Default for BoardScale {
+    fn default() -> Self {
+        BoardScale(1.0)
+    }
+}
+
+// tags the grid of cells, so it can be resized along with the window
+#[derive(Component)]
+struct Board;
+
+// tracks the score of a best-of series of two-player games
+// "player one" is whoever started the series as X, so that scores follow the players if they swap sides
+#[derive(Resource)]
+struct Series {
+    player_one: Mark,
+    wins: [usize; 2],
+    games: usize,
+}
+
+impl Default for Series {
+    fn default() -> Self {
+        Series { player_one: Mark::X, wins: [0, 0], games: 0 }
+    }
+}
+
+impl Series {
+    fn player(&self, mark: Mark) -> usize {
+        if mark == self.player_one { 0 } else { 1 }
+    }
+
+    fn mark(&self, player: usize) -> Mark {
+        match (player, self.player_one) {
+            (0, mark) => mark,
+            (_, Mark::X) => Mark::O,
+            (_, Mark::O) => Mark::X,
+        }
+    }
+
+    fn record(&mut self, winner: Option<Mark>) {
+        if let Some(mark) = winner {
+            self.wins[self.player(mark)] += 1;
         }
+        self.games += 1;
     }
 
-    fn is_corner(&self) -> bool {
-        *self == Self::TopLeft || *self == Self::TopRight || *self == Self::BottomLeft || *self == Self::BottomRight
+    // a series is decided once either player has won a majority of the games, or all games have been played
+    fn decided(&self, length: MatchLength) -> bool {
+        let majority = length.games() / 2 + 1;
+        self.wins.iter().any(|wins| *wins >= majority) || self.games >= length.games()
+    }
+
+    // the player with the most wins, if either has more than the other
+    fn leader(&self) -> Option<usize> {
+        match self.wins[0].cmp(&self.wins[1]) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    fn label(&self, font: Handle<Font>) -> Vec<TextSection> {
+        let style = |color| TextStyle { font: font.clone(), font_size: 40.0, color };
+        let (one, two) = (self.mark(0), self.mark(1));
+
+        vec![
+            TextSection::new("Player 1 (", style(Color::BLACK)),
+            TextSection::new(one.to_string(), style(one.color())),
+            TextSection::new(format!(")  {} - {}  Player 2 (", self.wins[0], self.wins[1]), style(Color::BLACK)),
+            TextSection::new(two.to_string(), style(two.color())),
+            TextSection::new(")", style(Color::BLACK)),
+        ]
+    }
+}
+
+// tags the text showing how many overtime rounds have been played, with Overtime::SuddenDeath
+#[derive(Component)]
+struct OvertimeLabel;
+
+// tags the text showing how many empty cells are left, with CellsLeft::ShowCellsLeft
+#[derive(Component)]
+struct CellsLeftLabel;
+
+// tags the text showing each player's mark and the series score in two-player games
+#[derive(Component)]
+struct SeriesLabel;
+
+#[derive(Resource, Default)]
+struct StateInfo {
+    game: Game,
+    current_player: Mark,
+    computer_thinking_time: Timer,
+    puzzle: Option<Goal>,
+    puzzle_solved: Option<bool>,
+    awaiting_release: bool, // true from when a mark is placed until the mouse button is released
+    pending_cell: Option<Cell>, // with TapToConfirm::TwoTaps, the cell tapped once, which must be tapped again
+    pressed_cell: Option<Cell>, // with PlaceOn::PlaceOnRelease, the cell the mouse button went down on, if any
+    replay_time: Timer,
+    restart_time: Timer,
+    overtime_rounds: usize, // with Overtime::SuddenDeath, how many times the board has been cleared after a tie
+    game_seed: Option<u64>, // drawn from the GameRng when the computer first moves in a game (see generate_computer_input())
+    candidates: Vec<(Cell, i8)>, // in debug builds, the cells the computer just weighed up, best first, until they're shown
+    shake: bool, // set when a click or tap is ignored, until the board starts shaking
+    lesson: Option<String>, // a note for the human (e.g. on their last move, with Teaching::Explain), until it's shown
+    confirm_leave: bool, // set when "back to menu" would throw away the score of a series, until the prompt is shown
+    announcement: Option<(Mark, Cell)>, // with MoveAnnouncement::AnnounceMoves, where the computer just moved, until it's shown
+    resigned: Option<Mark>, // whoever resigned the game (see resign()), if that's how it ended
+}
+
+impl StateInfo {
+    // like *self = StateInfo::default(), but in place, for another game on the same screen
+    // the timers are only rewound, so the computer's thinking time stays as it was last set
+    fn reset(&mut self) {
+        // destructured, so that any new field has to be considered here too
+        let StateInfo {
+            game, current_player, computer_thinking_time, puzzle, puzzle_solved, awaiting_release, pending_cell,
+            pressed_cell, replay_time, restart_time, overtime_rounds, game_seed, candidates, shake, lesson,
+            confirm_leave, announcement, resigned
+        } = self;
+
+        game.reset();
+        *current_player = Mark::default();
+        computer_thinking_time.reset();
+        *puzzle = None;
+        *puzzle_solved = None;
+        *awaiting_release = false;
+        *pending_cell = None;
+        *pressed_cell = None;
+        replay_time.reset();
+        restart_time.reset();
+        *overtime_rounds = 0;
+        *game_seed = None;
+        candidates.clear();
+        *shake = false;
+        *lesson = None;
+        *confirm_leave = false;
+        *announcement = None;
+        *resigned = None;
     }
 
-    fn hit(pos: Vec2) -> Option<Cell> {
-        match (Row::containing(pos.y), Column::containing(pos.x)) {
-            (None, _) | (_, None) => None,
-            (Some(row), Some(col)) => Some(Cell::from(row, col))
+    // whoever won, either by completing a line, or because the other player resigned
+    fn winner(&self) -> Option<Mark> {
+        match self.resigned {
+            Some(Mark::X) => Some(Mark::O),
+            Some(Mark::O) => Some(Mark::X),
+            None => self.game.winner().map(|(winner, _)| winner),
         }
     }
 }
 
-#[derive(Enumerated, Clone, Copy)]
-enum Line {
-    BottomRow,
-    MiddleRow,
-    TopRow,
-    LeftColumn,
-    MiddleColumn,
-    RightColumn,
-    UpDiagonal,
-    DownDiagonal,
+pub fn plugin(app: &mut App) {
+    app
+        .insert_resource(HumanMark::default())
+        .insert_resource(MenuHumanMark::default())
+        .insert_resource(StateInfo::default())
+        .insert_resource(Series::default())
+        .insert_resource(BoardScale::default())
+        .insert_resource(GameHost::default())
+        .insert_resource(WindowFocus(true))
+        .insert_resource(Resume::default())
+        .insert_resource(Practice::default())
+        .add_systems(PreUpdate, track_window_focus)
+        .add_systems(Last, keep_awake.run_if(resource_equals(PowerSaving::LowPower)).run_if(in_state(AppState::Game)))
+        .add_systems(OnEnter(AppState::Game), (start_game, apply_theme, start_countdown).chain())
+        .add_systems(OnExit(AppState::Game), (remove_theme, restore_human_mark, clear_entities::<CountdownLabel>, stop_practice, stop_hosting))
+        .add_systems(OnEnter(AppState::Game), start_hosting)
+        .add_systems(Update, broadcast_game.run_if(in_state(AppState::Game)))
+        .add_systems(Update, count_down.run_if(in_state(AppState::Game)).run_if(help_hidden))
+        .init_state::<GameState>()
+        .add_systems(OnEnter(GameState::GameNotInProgress), tear_down_game)
+        .add_systems(OnEnter(GameState::XTurn), (start_x_turn, keep_snapshot, prompt_turn).chain())
+        .add_systems(Update, capture_input.run_if(in_state(GameState::XTurn)).run_if(help_hidden).run_if(countdown_over))
+        .add_systems(OnEnter(GameState::OTurn), (start_o_turn, keep_snapshot, prompt_turn).chain())
+        .add_systems(Update, capture_input.run_if(in_state(GameState::OTurn)).run_if(help_hidden).run_if(countdown_over))
+        .insert_resource(NotationEntry::default())
+        .add_systems(Update, type_notation.run_if(debug_mode).run_if(help_hidden).run_if(in_state(GameState::XTurn).or_else(in_state(GameState::OTurn))))
+        .add_systems(Update, hint_lines.run_if(in_state(GameState::XTurn).or_else(in_state(GameState::OTurn))))
+        .add_systems(PostUpdate, check_marks.run_if(debug_mode).run_if(in_state(GameState::XTurn).or_else(in_state(GameState::OTurn))))
+        .add_systems(Update, check_transitions.run_if(debug_mode))
+        .add_systems(OnExit(GameState::XTurn), clear_highlights)
+        .add_systems(OnExit(GameState::OTurn), clear_highlights)
+        .add_systems(Update, copy_notation.run_if(debug_mode).run_if(in_state(AppState::Game)))
+        .add_systems(Update, save_snapshot.run_if(debug_mode).run_if(in_state(AppState::Game)))
+        .add_systems(Update, copy_transcript.run_if(in_state(AppState::Game)))
+        .add_systems(Update, undo_move.run_if(undo_pressed).run_if(help_hidden).run_if(in_state(GameState::XTurn).or_else(in_state(GameState::OTurn))))
+        .add_systems(Update, resign.run_if(resign_pressed).run_if(help_hidden).run_if(in_state(GameState::XTurn).or_else(in_state(GameState::OTurn))))
+        .add_systems(Update, (show_candidates, fade_candidates).run_if(debug_mode).run_if(in_state(AppState::Game)))
+        .add_systems(OnEnter(GameState::Overtime), start_overtime)
+        .add_systems(Update, update_overtime_label.run_if(in_state(AppState::Game)))
+        .add_systems(Update, update_cells_left_label.run_if(in_state(AppState::Game)))
+        .add_systems(OnEnter(GameState::InstantReplay), start_instant_replay)
+        .add_systems(Update, instant_replay.run_if(in_state(GameState::InstantReplay)))
+        .add_systems(OnExit(GameState::InstantReplay), clear_highlights)
+        .add_systems(OnEnter(GameState::GameOver), game_over)
+        .add_systems(OnEnter(GameState::GameOver), discard_snapshot)
+        .add_systems(OnEnter(GameState::GameOver), keep_recording)
+        .add_systems(OnEnter(GameState::GameOver), rate_skill.after(game_over).after(keep_recording))
+        .add_systems(OnEnter(GameState::GameOver), start_restart_timer)
+        .add_systems(OnEnter(GameState::GameOver), highlight_winning_line)
+        .add_systems(OnEnter(GameState::GameOver), number_moves_at_end)
+        .add_systems(Update, see_through_overlay.run_if(in_state(GameState::GameOver)))
+        .add_systems(OnExit(GameState::GameOver), clear_highlights)
+        .add_systems(Update, game_over_buttons.run_if(in_state(GameState::GameOver)).run_if(help_hidden).run_if(leave_unconfirmed))
+        .add_systems(Update, (confirm_leave, leave_buttons).chain().run_if(in_state(GameState::GameOver)).run_if(help_hidden))
+        .add_systems(Update, update_series_label.run_if(in_state(AppState::Game)))
+        .add_systems(Update, (rescale_board, resize_board).chain())
+        .add_systems(Update, animate_ripples)
+        .add_systems(Update, (start_shake, animate_shake).chain().run_if(in_state(AppState::Game)))
+        .add_systems(Update, (show_lesson, fade_lesson).run_if(in_state(AppState::Game)))
+        .add_systems(Update, (show_announcement, fade_announcement).run_if(in_state(AppState::Game)))
+        .add_systems(Update, pulse_turn_prompt.run_if(in_state(AppState::Game)))
+        .add_systems(Update, cue_resume.run_if(resource_equals(ResumeCue::ShowResumeCue)).run_if(in_state(GameState::XTurn).or_else(in_state(GameState::OTurn))))
+        .add_systems(Update, animate_hop.run_if(in_state(AppState::Game)))
+        // in PostUpdate, so that marks are shaded before they're ever drawn
+        .add_systems(PostUpdate, (color_marks, shade_computer_marks).chain().run_if(in_state(AppState::Game)))
+        .add_systems(OnExit(GameState::GameOver), clear_entities::<Mark>)
+        .add_systems(OnExit(GameState::GameOver), clear_entities::<MoveNumber>)
+        .add_systems(OnExit(GameState::GameOver), clear_entities::<GameOverOverlay>);
 }
 
-impl Line {
-    fn cells(&self) -> [Cell; 3] {
-        match self {
-            Self::BottomRow => [Cell::BottomLeft, Cell::BottomMiddle, Cell::BottomRight],
-            Self::MiddleRow => [Cell::MiddleLeft, Cell::MiddleMiddle, Cell::MiddleRight],
-            Self::TopRow => [Cell::TopLeft, Cell::TopMiddle, Cell::TopRight],
-            Self::LeftColumn => [Cell::TopLeft, Cell::MiddleLeft, Cell::BottomLeft],
-            Self::MiddleColumn => [Cell::TopMiddle, Cell::MiddleMiddle, Cell::BottomMiddle],
-            Self::RightColumn => [Cell::TopRight, Cell::MiddleRight, Cell::BottomRight],
-            Self::UpDiagonal => [Cell::BottomLeft, Cell::MiddleMiddle, Cell::TopRight],
-            Self::DownDiagonal => [Cell::TopLeft, Cell::MiddleMiddle, Cell::BottomRight],
+// however a game ended, leaving it always passes through GameNotInProgress, so all teardown happens here
+//
+// only the game screen is despawned (not every AppState screen), because the screen we're moving to may already
+// have been drawn by the time this runs
+fn tear_down_game(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    mut entry: ResMut<NotationEntry>,
+    leftovers: Query<Entity, Or<(OnBoard, With<GameOverOverlay>)>>,
+    screens: Query<(Entity, &AppState)>,
+) {
+    *info = StateInfo::default();
+    entry.0.clear();
+
+    for entity in &leftovers {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (entity, screen) in &screens {
+        if *screen == AppState::Game {
+            commands.entity(entity).despawn_recursive();
         }
     }
 }
 
-#[derive(Component, Default, PartialEq, Eq, Clone, Copy, Hash)]
-enum Mark {
-    #[default]
-    X,
-    O
+// the HumanMark chosen on the menu, kept aside while the game screen is showing, as the HumanMark resource can change
+// from game to game: with HumanMark::HumanRandom, the human's mark is picked at the start of every game and written to
+// the resource, so everything else only ever sees X or O, and sudden death overtime (see start_overtime()) swaps it
+//
+// every game starts from the menu's choice again, and it's put back when leaving the game screen
+#[derive(Resource, Default)]
+pub struct MenuHumanMark(Option<HumanMark>);
+
+pub fn resolve_human_mark(human_mark: &mut HumanMark, menu: &mut MenuHumanMark, rng: &mut GameRng) {
+    *human_mark = *menu.0.get_or_insert(*human_mark);
+
+    if *human_mark == HumanMark::HumanRandom {
+        *human_mark = *[HumanMark::HumanX, HumanMark::HumanO].choose(rng).expect("array is non-empty, so we should always get a value");
+        info!("the human plays {} this game", if *human_mark == HumanMark::HumanX { "X" } else { "O" });
+    }
+}
+
+pub fn restore_human_mark(mut human_mark: ResMut<HumanMark>, mut menu: ResMut<MenuHumanMark>) {
+    if let Some(chosen) = menu.0.take() {
+        *human_mark = chosen;
+    }
+}
+
+// with Hosting, the game being played, which spectators can watch (see spectate.rs) until the game screen is left
+#[derive(Resource, Default)]
+struct GameHost(Option<Host>);
+
+fn start_hosting(hosting: Res<Hosting>, mut host: ResMut<GameHost>) {
+    if *hosting == Hosting::NotHosting { return; }
+
+    match Host::bind(PORT) {
+        Ok(bound) => {
+            info!("hosting on port {}", PORT);
+            host.0 = Some(bound);
+        }
+        Err(error) => warn!("could not host on port {}: {}", PORT, error),
+    }
+}
+
+// every frame, not just when the game changes, so that anyone who's just connected is let in straight away
+fn broadcast_game(info: Res<StateInfo>, mut host: ResMut<GameHost>) {
+    if let Some(host) = host.0.as_mut() {
+        host.update(&info.game);
+    }
+}
+
+// the spectators see the connection close, and that the game has ended
+fn stop_hosting(mut host: ResMut<GameHost>) {
+    host.0 = None;
+}
+
+// where the game in progress is kept, so it can be picked back up from the menu (with "continue") after leaving it, or
+// even after quitting
+pub const SNAPSHOT_FILE: &str = "tic-tac-toe-snapshot.txt";
+
+// the snapshot the next game starts from, set when "continue" is pressed on the menu
+#[derive(Resource, Default)]
+pub struct Resume(pub Option<GameSnapshot>);
+
+// at the start of every turn, the game so far is saved, in case it's left before it's over (puzzles aren't saved)
+fn keep_snapshot(
+    info: Res<StateInfo>,
+    series: Res<Series>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    human_mark: Res<HumanMark>,
+) {
+    save_game(&info, &series, *game_mode, *difficulty, *human_mark);
+}
+
+fn save_game(info: &StateInfo, series: &Series, game_mode: GameMode, difficulty: Difficulty, human_mark: HumanMark) {
+    if game_mode == GameMode::Puzzle { return; }
+
+    let snapshot = GameSnapshot {
+        notation: info.game.to_notation(),
+        game_mode,
+        difficulty,
+        human_mark,
+        player_one: series.player_one,
+        wins: series.wins,
+        games: series.games,
+    };
+
+    if let Err(error) = snapshot.save(SNAPSHOT_FILE) {
+        warn!("could not save the game to {}: {}", SNAPSHOT_FILE, error);
+    }
+}
+
+// where the last finished one-player game is kept, so it can be practised against from the menu (with "practice")
+pub const RECORDING_FILE: &str = "tic-tac-toe-recording.txt";
+
+// the moves of the recorded game being practised against, if any, set when "practice" is pressed on the menu
+// it's kept for every game until the game screen is left, so "play again" tries the same game again
+#[derive(Resource, Default)]
+pub struct Practice(pub Option<Vec<Cell>>);
+
+// games played while practising aren't recorded, so the same game can be practised against until it's been beaten
+fn keep_recording(
+    info: Res<StateInfo>,
+    practice: Res<Practice>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    human_mark: Res<HumanMark>,
+) {
+    if *game_mode != GameMode::OnePlayer || practice.0.is_some() { return; }
+
+    // a game which didn't start from an empty board (e.g. one which was picked back up, see Game::from_notation()) doesn't
+    // know the order its marks were made in, so it can't be replayed
+    if info.game.transcript().is_none() { return; }
+
+    let recording = Recording { human_mark: *human_mark, difficulty: *difficulty, moves: info.game.history().to_vec() };
+
+    if let Err(error) = recording.save(RECORDING_FILE) {
+        warn!("could not save the game to {}: {}", RECORDING_FILE, error);
+    }
+}
+
+// the human's Skill is updated after every one-player game (except when practising, as the computer's moves are mostly
+// recorded ones), and with AutoDifficulty, the next game is played at whichever Difficulty now suits them best
+fn rate_skill(
+    info: Res<StateInfo>,
+    practice: Res<Practice>,
+    game_mode: Res<GameMode>,
+    human_mark: Res<HumanMark>,
+    auto_difficulty: Res<AutoDifficulty>,
+    mut difficulty: ResMut<Difficulty>,
+    mut skill: ResMut<Skill>,
+) {
+    if *game_mode != GameMode::OnePlayer || practice.0.is_some() || info.puzzle.is_some() { return; }
+
+    skill.update(*difficulty, human_result(&info, *human_mark));
+
+    if *auto_difficulty == AutoDifficulty::AutoDifficulty && skill.pick() != *difficulty {
+        *difficulty = skill.pick();
+        info!("rated {:.0}, so the next game is {}", skill.rating(), *difficulty);
+    }
+}
+
+// how a finished one-player game went for the human
+fn human_result(info: &StateInfo, human_mark: HumanMark) -> GameResult {
+    match info.winner() {
+        None => GameResult::Draw,
+        Some(winner) if winner.is(human_mark) => GameResult::Win,
+        Some(_) => GameResult::Loss,
+    }
+}
+
+fn stop_practice(mut practice: ResMut<Practice>) {
+    practice.0 = None;
+}
+
+fn discard_snapshot() {
+    if let Err(error) = GameSnapshot::discard(SNAPSHOT_FILE) {
+        warn!("could not remove {}: {}", SNAPSHOT_FILE, error);
+    }
+}
+
+fn start_x_turn(mut info: ResMut<StateInfo>) {
+    info.current_player = Mark::X
+}
+
+fn start_o_turn(mut info: ResMut<StateInfo>) {
+    info.current_player = Mark::O
+}
+
+// where a new game starts from: a puzzle, a position from the board editor, a game left part of the way through, or
+// (if none of those) an empty board
+#[derive(SystemParam)]
+struct GameSetup<'w> {
+    game_mode: Res<'w, GameMode>,
+    selected_puzzle: Res<'w, SelectedPuzzle>,
+    edited: ResMut<'w, EditedPosition>,
+    resume: ResMut<'w, Resume>,
+}
+
+// which mark the human plays, along with what's needed to pick one with HumanMark::HumanRandom
+#[derive(SystemParam)]
+pub struct HumanSide<'w> {
+    pub human_mark: ResMut<'w, HumanMark>,
+    pub menu_human_mark: ResMut<'w, MenuHumanMark>,
+    pub rng: ResMut<'w, GameRng>,
+}
+
+impl HumanSide<'_> {
+    // see resolve_human_mark()
+    pub fn resolve(&mut self) {
+        resolve_human_mark(&mut self.human_mark, &mut self.menu_human_mark, &mut self.rng);
+    }
+}
+
+// everything needed to draw the board, and the marks on it
+#[derive(SystemParam)]
+struct BoardView<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    scale: Res<'w, BoardScale>,
+    outline: Res<'w, BoardOutline>,
+    cells: Query<'w, 's, (Entity, &'static Cell)>,
+    previews: Query<'w, 's, Entity, With<Preview>>,
 }
 
-impl std::fmt::Display for Mark {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Mark::X => write!(f, "X"),
-            Mark::O => write!(f, "O"),
+fn start_game(
+    mut commands: Commands,
+    board: BoardView,
+    mut info: ResMut<StateInfo>,
+    setup: GameSetup,
+    mut series: ResMut<Series>,
+    mut side: HumanSide,
+    mut next_game_state: ResMut<NextState<GameState>>
+) {
+    let BoardView { asset_server, scale, outline, .. } = board;
+    let GameSetup { game_mode, selected_puzzle, mut edited, mut resume } = setup;
+
+    let font: Handle<Font> = asset_server.load("fonts/larabie.otf");
+
+    side.resolve();
+
+    // every visit to the game screen starts a fresh series
+    *series = Series::default();
+
+    // puzzles start from a preset position, with whichever player is next to move
+    let puzzle = match (*game_mode, selected_puzzle.0) {
+        (GameMode::Puzzle, Some(index)) => Some(&PUZZLES[index]),
+        _ => None
+    };
+
+    if let Some(puzzle) = puzzle {
+        info.game = Game::from_notation(puzzle.notation).expect("built-in puzzles should be valid");
+        info.puzzle = Some(puzzle.goal);
+    }
+
+    // a position built in the board editor (see editor.rs) is only played once
+    if edited.play {
+        edited.play = false;
+        info.game = edited.board.clone();
+    }
+
+    // a game left part of the way through (see keep_snapshot()), with its series score, if any
+    if let Some(snapshot) = resume.0.take() {
+        info.game = Game::from_notation(&snapshot.notation).expect("snapshots are checked when they're loaded");
+        *series = Series { player_one: snapshot.player_one, wins: snapshot.wins, games: snapshot.games };
+    }
+
+    // a loaded position might already be decided, in which case capture_input() ends the game on the first turn
+    match info.game.next_player() {
+        Mark::X => next_game_state.set(GameState::XTurn),
+        Mark::O => next_game_state.set(GameState::OTurn),
+    }
+
+    fn cell<'a>(parent: &'a mut ChildBuilder, cell: Cell, border: UiRect, game: &Game, font: &Handle<Font>, scale: f32) -> EntityCommands<'a> {
+        let (grid_row, grid_column) = cell.grid_placement();
+
+        let mut entity = parent.spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_row: GridPlacement::start(grid_row),
+                    grid_column: GridPlacement::start(grid_column),
+                    justify_items: JustifyItems::Center,
+                    align_items: AlignItems::Center,
+                    border,
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                ..default()
+            },
+            cell
+        ));
+
+        // draw any marks already on the board (e.g. in a puzzle)
+        if let Some(mark) = game.get(cell) {
+            entity.with_children(|parent| spawn_mark(parent, mark, font.clone(), scale));
         }
+
+        entity
     }
+
+    draw_screen(&mut commands, AppState::Game).with_children(|parent| {
+        if let Some(puzzle) = puzzle {
+            let mark = info.game.next_player();
+
+            parent.spawn(
+                TextBundle::from_sections([
+                    TextSection::new(mark.to_string(), TextStyle { font: font.clone(), font_size: 40.0, color: mark.color() }),
+                    TextSection::new(format!(" to move: {}", puzzle.goal), TextStyle { font: font.clone(), font_size: 40.0, color: Color::BLACK }),
+                ]).with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(5.0),
+                    ..default()
+                })
+            );
+        }
+
+        if *game_mode == GameMode::TwoPlayers {
+            parent.spawn((
+                TextBundle::from_sections(series.label(font.clone())).with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(5.0),
+                    ..default()
+                }),
+                SeriesLabel
+            ));
+        }
+
+        parent.spawn((
+            TextBundle::from_section("", TextStyle { font: font.clone(), font_size: 30.0, color: Color::BLACK }).with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(50.0),
+                ..default()
+            }),
+            OvertimeLabel
+        ));
+
+        parent.spawn((
+            TextBundle::from_section("", TextStyle { font: font.clone(), font_size: 30.0, color: Color::BLACK }).with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(5.0),
+                right: Val::Px(10.0),
+                ..default()
+            }),
+            CellsLeftLabel
+        ));
+
+        if debug_mode() {
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { font: font.clone(), font_size: 30.0, color: Color::GRAY }).with_style(Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(5.0),
+                    ..default()
+                }),
+                NotationEntry::default()
+            ));
+        }
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_template_rows: vec![GridTrack::flex(1.0), GridTrack::flex(1.0), GridTrack::flex(1.0)],
+                    grid_template_columns: vec![GridTrack::flex(1.0), GridTrack::flex(1.0), GridTrack::flex(1.0)],
+                    width: Val::Px(3.0 * Column::SPACING * scale.0),
+                    height: Val::Px(3.0 * Row::SPACING * scale.0),
+                    ..default()
+                },
+                ..default()
+            },
+            Board
+        )).with_children(|parent| {
+            for c in Cell::variants() {
+                cell(parent, c, cell_border(c, *outline), &info.game, &font, scale.0);
+            }
+        });
+    });
+}
+
+// the four grid lines are drawn by the cells in the left and right columns, and top and bottom rows, on their inner
+// edges, so the middle cell has no border of its own
+// with BoardOutline::Outlined, those same cells also draw a line around the outside of the board
+fn cell_border(cell: Cell, outline: BoardOutline) -> UiRect {
+    const NONE: Val = Val::ZERO;
+    const THIN: Val = Val::Px(6.0);
+
+    let (row, column) = (cell.row(), cell.column());
+    let outlined = outline == BoardOutline::Outlined;
+
+    let edge = |inner: bool, outer: bool| if inner || (outer && outlined) { THIN } else { NONE };
+
+    UiRect::new(
+        edge(column == Column::Right, column == Column::Left),
+        edge(column == Column::Left, column == Column::Right),
+        edge(row == Row::Bottom, row == Row::Top),
+        edge(row == Row::Top, row == Row::Bottom),
+    )
+}
+
+// in debug builds, a board can be typed in notation (see Game::from_notation()) during any turn, to set up a position
+fn debug_mode() -> bool {
+    cfg!(debug_assertions)
+}
+
+// the notation typed so far, which is also a Component tagging the text showing it
+#[derive(Resource, Component, Default)]
+struct NotationEntry(String);
+
+// the keys typed so far, and the label showing them
+#[derive(SystemParam)]
+struct NotationTyping<'w, 's> {
+    keys: EventReader<'w, 's, KeyboardInput>,
+    entry: ResMut<'w, NotationEntry>,
+    labels: Query<'w, 's, &'static mut Text, With<NotationEntry>>,
 }
 
-impl Mark {
-    fn color(&self) -> Color {
-        match self {
-            Mark::X => Color::RED,
-            Mark::O => Color::BLUE,
+fn type_notation(
+    mut commands: Commands,
+    board: BoardView,
+    typing: NotationTyping,
+    mut info: ResMut<StateInfo>,
+    leftovers: Query<Entity, OnBoard>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let BoardView { asset_server, scale, cells, .. } = board;
+    let NotationTyping { mut keys, mut entry, mut labels } = typing;
+
+    let mut submitted = false;
+
+    for event in keys.read().filter(|event| event.state == ButtonState::Pressed) {
+        match &event.logical_key {
+            Key::Character(c) if entry.0.len() < Cell::CARDINALITY && ["X", "O", "."].contains(&c.to_uppercase().as_str()) => {
+                entry.0.push_str(&c.to_uppercase())
+            }
+            Key::Backspace => { entry.0.pop(); }
+            Key::Escape => entry.0.clear(),
+            Key::Enter => submitted = true,
+            _ => {}
+        }
+    }
+
+    if submitted {
+        match Game::from_notation(&entry.0) {
+            Err(error) => warn!("could not load board: {}", error),
+            Ok(game) => {
+                info!("loaded board \"{}\"", entry.0);
+
+                for entity in &leftovers {
+                    commands.entity(entity).despawn_recursive();
+                }
+
+                for (entity, cell) in &cells {
+                    if let Some(mark) = game.get(*cell) {
+                        commands.entity(entity).with_children(|parent| {
+                            spawn_mark(parent, mark, asset_server.load("fonts/larabie.otf"), scale.0)
+                        });
+                    }
+                }
+
+                // the state might not change (e.g. from XTurn to XTurn), so set the current player here, too
+                let current_player = game.next_player();
+
+                match current_player {
+                    _ if game.over() => next_game_state.set(GameState::GameOver),
+                    Mark::X => next_game_state.set(GameState::XTurn),
+                    Mark::O => next_game_state.set(GameState::OTurn),
+                }
+
+                *info = StateInfo { game, current_player, ..default() };
+                entry.0.clear();
+            }
         }
     }
 
-    fn is(&self, human_mark: HumanMark) -> bool {
-        match self {
-            Mark::X if human_mark == HumanMark::HumanX => true,
-            Mark::O if human_mark == HumanMark::HumanO => true,
-            _ => false
+    if entry.is_changed() {
+        for mut text in labels.iter_mut() {
+            text.sections[0].value = entry.0.clone();
         }
     }
 }
 
-// Game is inside game module so private fields of Game cannot be accessed / mutated directly
-mod game {
-    use bevy::utils::{HashMap, HashSet};
+// in debug builds, ctrl + c copies the board in notation, so that a position can be shared in a bug report
+// there's no clipboard access on the web, so there it's just logged to the browser console instead
+fn copy_notation(keys: Res<ButtonInput<KeyCode>>, info: Res<StateInfo>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if !(ctrl && !shift && keys.just_pressed(KeyCode::KeyC)) { return; }
 
-    use crate::game::{Cell, Column, Line, Mark, Row};
+    copy_to_clipboard("board", info.game.to_notation());
+}
 
-    // All of Game's fields are private so that we can recalculate the winner when a new mark is made on the board
-    // impl Default is required for impl Default on StateInfo
-    #[derive(Default)]
-    pub struct Game {
-        marks: HashMap<Cell, Option<Mark>>,
-        winner: Option<(Mark, Line)>,
-        over: bool
+// how many moves ctrl + z takes back, with `current_player` to move (see undo_move())
+fn plies_to_undo(game_mode: GameMode, current_player: Mark, human_mark: HumanMark) -> usize {
+    match game_mode {
+        GameMode::OnePlayer if current_player.is(human_mark) => 2,
+        _ => 1,
     }
+}
 
-    impl Game {
-        const WINNING_ARRANGEMENTS: [(fn(&(&Cell, &Option<Mark>)) -> bool, Line); 8] = [
-            (|(cell, _)| cell.row() == Row::Top, Line::TopRow),
-            (|(cell, _)| cell.row() == Row::Middle, Line::MiddleRow),
-            (|(cell, _)| cell.row() == Row::Bottom, Line::BottomRow),
-            (|(cell, _)| cell.column() == Column::Left, Line::LeftColumn),
-            (|(cell, _)| cell.column() == Column::Middle, Line::MiddleColumn),
-            (|(cell, _)| cell.column() == Column::Right, Line::RightColumn),
-            (|(cell, _)| cell.column().position() == cell.row().position(), Line::UpDiagonal),
-            (|(cell, _)| cell.column().position() == -cell.row().position(), Line::DownDiagonal),
-        ];
+// who is playing whom: one or two players, which mark the human plays, and (against the computer) how well it plays
+#[derive(SystemParam)]
+pub struct Matchup<'w> {
+    pub game_mode: Res<'w, GameMode>,
+    pub human_mark: Res<'w, HumanMark>,
+    pub difficulty: Res<'w, Difficulty>,
+}
+
+// when to run undo_move()
+fn undo_pressed(keys: Res<ButtonInput<KeyCode>>) -> bool {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+    ctrl && keys.just_pressed(KeyCode::KeyZ)
+}
+
+// ctrl + z takes back the last move
+//
+// in one-player games, the computer's reply is taken back along with the human's move before it, so that it's always
+// the human's turn afterwards (while the computer is still thinking, only the human's move needs to be taken back)
+fn undo_move(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    cells: Query<(&Cell, &Children)>,
+    leftovers: Query<(), OnBoard>,
+    series: Res<Series>,
+    matchup: Matchup,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let Matchup { game_mode, human_mark, difficulty } = matchup;
+
+    // a puzzle is over after a single move, so there's never anything to take back
+    if info.puzzle.is_some() { return; }
+
+    let plies = plies_to_undo(*game_mode, info.current_player, *human_mark);
+
+    // a loaded board (e.g. a game which was picked back up) doesn't know the order of its marks, so only moves made since
+    // it was loaded can be taken back (see Game::undoable_moves())
+    if info.game.undoable_moves() < plies {
+        info!("there are no moves to take back");
+        return;
+    }
+
+    for _ in 0..plies {
+        let Some(cell) = info.game.undo() else { return; };
+        info!("took back {:?}", cell);
+
+        for (_, children) in cells.iter().filter(|(c, _)| **c == cell) {
+            for child in children.iter().filter(|child| leftovers.contains(**child)) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+    }
+
+    // whoever made the last move taken back gets to make it again
+    info.current_player = info.game.next_player();
+    info.pending_cell = None;
+    info.candidates.clear();
+    info.lesson = None;
+    info.computer_thinking_time.reset();
+
+    // moving to the same state again does nothing, so the game is saved here too, rather than in keep_snapshot()
+    save_game(&info, &series, *game_mode, *difficulty, *human_mark);
 
-        fn determine_winner(marks: &HashMap<Cell, Option<Mark>>) -> Option<(Mark, Line)> {
-            for (arrangement, line) in Self::WINNING_ARRANGEMENTS {
-                let marks = marks.iter()
-                    .filter(arrangement)
-                    .flat_map(|(_, mark)| *mark)
-                    .collect::<Vec<Mark>>();
+    next_game_state.set(match info.current_player {
+        Mark::X => GameState::XTurn,
+        Mark::O => GameState::OTurn,
+    });
+}
+
+// in any build, ctrl + shift + c copies the moves made so far (see Game::transcript()), e.g. to share a game
+fn copy_transcript(keys: Res<ButtonInput<KeyCode>>, info: Res<StateInfo>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if !(ctrl && shift && keys.just_pressed(KeyCode::KeyC)) { return; }
+
+    match info.game.transcript() {
+        Some(transcript) => copy_to_clipboard("moves", transcript),
+        None => info!("the order of the moves on a loaded board isn't known, so there are no moves to copy"),
+    }
+}
+
+// there's no clipboard we can reach on the web, so there the text is just logged
+fn copy_to_clipboard(what: &str, text: String) {
+    #[cfg(not(target_arch = "wasm32"))]
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text.clone())) {
+        Ok(()) => info!("copied {} \"{}\" to the clipboard", what, text),
+        Err(error) => warn!("could not copy {} \"{}\" to the clipboard: {}", what, text, error),
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    info!("{}: \"{}\"", what, text);
+}
+
+// in debug builds, F12 saves a picture of the game screen to a PNG file, to share a position as an image
+// on the web, the browser downloads the file instead
+//
+// with shift held, the game over overlay (if any) is hidden for the frame which is captured, so just the board is saved
+fn save_snapshot(
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut overlays: Query<&mut Visibility, With<GameOverOverlay>>,
+    mut hidden: Local<bool>,
+    mut count: Local<usize>,
+) {
+    // the screenshot was taken of the last frame drawn, so the overlay can come back now
+    if *hidden {
+        for mut visibility in overlays.iter_mut() {
+            *visibility = Visibility::Inherited;
+        }
+        *hidden = false;
+    }
+
+    if !keys.just_pressed(KeyCode::F12) { return; }
+
+    let Ok(window) = windows.get_single() else { return; };
+
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        for mut visibility in overlays.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+        *hidden = true;
+    }
+
+    *count += 1;
+    let path = format!("tic-tac-toe-board-{}.png", *count);
+
+    match screenshots.save_screenshot_to_disk(window, &path) {
+        Ok(()) => info!("saving a picture of the board to {}", path),
+        Err(error) => warn!("could not save a picture of the board to {}: {}", path, error),
+    }
+}
+
+// when to run resign()
+fn resign_pressed(keys: Res<ButtonInput<KeyCode>>) -> bool {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+    ctrl && keys.just_pressed(KeyCode::KeyR)
+}
+
+// ctrl + r resigns the game, which the other player wins: in one-player games, it's the human who resigns, even while
+// the computer is thinking, and in two-player games, it's whoever's turn it is
+fn resign(
+    mut info: ResMut<StateInfo>,
+    game_mode: Res<GameMode>,
+    human_mark: Res<HumanMark>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    // a puzzle is over after a single move, so there's nothing to give up on
+    if info.puzzle.is_some() { return; }
+
+    let resigned = match *game_mode {
+        GameMode::OnePlayer if Mark::X.is(*human_mark) => Mark::X,
+        GameMode::OnePlayer => Mark::O,
+        _ => info.current_player,
+    };
+
+    info!("{} resigned", resigned);
+    info.resigned = Some(resigned);
+    next_game_state.set(GameState::GameOver);
+}
+
+// in debug builds, after the computer moves, its top three choices are labelled with their rank and weight for a
+// couple of seconds (see rank_cells()), to make it easier to see why it chose the cell it did
+#[derive(Component)]
+struct CandidateLabel(Timer);
+
+fn show_candidates(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    cells: Query<(Entity, &Cell)>,
+    asset_server: Res<AssetServer>,
+    scale: Res<BoardScale>,
+    game_speed: Res<GameSpeed>,
+) {
+    if info.candidates.is_empty() { return; }
+
+    for (rank, (cell, weight)) in std::mem::take(&mut info.candidates).into_iter().take(3).enumerate() {
+        let Some((entity, _)) = cells.iter().find(|(_, c)| **c == cell) else { continue; };
+
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    format!("#{} ({})", rank + 1, weight),
+                    TextStyle {
+                        font_size: move_number_font_size(scale.0),
+                        font: asset_server.load("fonts/larabie.otf"),
+                        color: Color::DARK_GRAY,
+                    }
+                ).with_style(Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(15.0 * scale.0),
+                    left: Val::Px(25.0 * scale.0),
+                    ..default()
+                }),
+                CandidateLabel(Timer::new(game_speed.scale(Duration::from_secs(2)), TimerMode::Once))
+            ));
+        });
+    }
+}
+
+// everything needed to show a short-lived label on the game screen, which lasts longer or shorter with the GameSpeed
+#[derive(SystemParam)]
+struct LabelView<'w, 's> {
+    screens: Query<'w, 's, (Entity, &'static AppState)>,
+    asset_server: Res<'w, AssetServer>,
+    game_speed: Res<'w, GameSpeed>,
+}
+
+// with TurnPrompt::ShowTurnPrompt, "your turn" pulses above the board for a second after the computer moves
+// (and with ResumeCue::ShowResumeCue, whose turn it is, when the game is picked back up, see cue_resume())
+#[derive(Component)]
+struct TurnPromptLabel(Timer);
+
+fn prompt_turn(
+    mut commands: Commands,
+    info: Res<StateInfo>,
+    game_mode: Res<GameMode>,
+    human_mark: Res<HumanMark>,
+    turn_prompt: Res<TurnPrompt>,
+    view: LabelView,
+) {
+    let LabelView { screens, asset_server, game_speed } = view;
+
+    if *turn_prompt == TurnPrompt::NoTurnPrompt || *game_mode != GameMode::OnePlayer { return; }
+
+    // only after the computer has moved, not at the start of a game
+    if !info.current_player.is(*human_mark) || info.game.history().is_empty() { return; }
+
+    let Some((screen, _)) = screens.iter().find(|(_, screen)| **screen == AppState::Game) else { return; };
+
+    commands.entity(screen).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "your turn",
+                TextStyle { font: asset_server.load("fonts/larabie.otf"), font_size: 40.0, color: info.current_player.color() }
+            ).with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                ..default()
+            }),
+            TurnPromptLabel(Timer::new(game_speed.scale(Duration::from_secs(1)), TimerMode::Once))
+        ));
+    });
+}
+
+// the help overlay pauses the game underneath it, so with ResumeCue::ShowResumeCue, closing it during a turn says whose
+// turn it is (like TurnPrompt::ShowTurnPrompt), and makes the board hop, to show that the game is live again
+fn cue_resume(
+    mut commands: Commands,
+    mut closed: RemovedComponents<HelpOverlay>,
+    info: Res<StateInfo>,
+    labels: Query<Entity, With<TurnPromptLabel>>,
+    boards: Query<Entity, With<Board>>,
+    view: LabelView,
+) {
+    let LabelView { screens, asset_server, game_speed } = view;
+
+    if closed.read().count() == 0 || info.game.over() { return; }
+
+    let Some((screen, _)) = screens.iter().find(|(_, screen)| **screen == AppState::Game) else { return; };
+
+    for entity in &labels {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mark = info.current_player;
+
+    commands.entity(screen).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                format!("{}'s turn", mark),
+                TextStyle { font: asset_server.load("fonts/larabie.otf"), font_size: 40.0, color: mark.color() }
+            ).with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                ..default()
+            }),
+            TurnPromptLabel(Timer::new(game_speed.scale(Duration::from_secs(1)), TimerMode::Once))
+        ));
+    });
+
+    for entity in &boards {
+        commands.entity(entity).insert(Hop(Timer::new(game_speed.scale(Duration::from_millis(400)), TimerMode::Once)));
+    }
+}
+
+// a quick hop of the whole board, up and back down again, moved with its Style, like a Shake
+#[derive(Component)]
+struct Hop(Timer);
+
+fn animate_hop(
+    mut commands: Commands,
+    mut boards: Query<(Entity, &mut Hop, &mut Style)>,
+    scale: Res<BoardScale>,
+    time: Res<Time>,
+) {
+    for (entity, mut hop, mut style) in boards.iter_mut() {
+        hop.0.tick(time.delta());
+
+        if hop.0.finished() {
+            style.top = Val::Auto;
+            commands.entity(entity).remove::<Hop>();
+            continue;
+        }
+
+        style.top = Val::Px(-12.0 * scale.0 * (hop.0.fraction() * std::f32::consts::PI).sin());
+    }
+}
+
+// pulses twice, then fades away, or disappears straight away once the human clicks or taps
+fn pulse_turn_prompt(
+    mut commands: Commands,
+    mut labels: Query<(Entity, &mut TurnPromptLabel, &mut Text)>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    touch_input: Res<Touches>,
+    time: Res<Time>,
+) {
+    let interacted = mouse_button_input.just_pressed(MouseButton::Left) || touch_input.any_just_pressed();
+
+    for (entity, mut label, mut text) in labels.iter_mut() {
+        label.0.tick(time.delta());
+
+        if interacted || label.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let progress = label.0.fraction();
+        let pulse = (progress * 2.0 * std::f32::consts::TAU).cos() * 0.5 + 0.5;
+
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(pulse * (1.0 - progress));
+        }
+    }
+}
+
+// with Teaching::Explain, a note on the human's last move is shown under the board for a few seconds
+#[derive(Component)]
+struct LessonLabel(Timer);
+
+fn show_lesson(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    labels: Query<Entity, With<LessonLabel>>,
+    view: LabelView,
+) {
+    let LabelView { screens, asset_server, game_speed } = view;
+
+    let Some(lesson) = info.lesson.take() else { return; };
+
+    // only the latest note is shown
+    for entity in &labels {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some((screen, _)) = screens.iter().find(|(_, screen)| **screen == AppState::Game) else { return; };
+
+    commands.entity(screen).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                lesson,
+                TextStyle { font: asset_server.load("fonts/larabie.otf"), font_size: 30.0, color: Color::DARK_GREEN }
+            ).with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(40.0),
+                ..default()
+            }),
+            LessonLabel(Timer::new(game_speed.scale(Duration::from_secs(3)), TimerMode::Once))
+        ));
+    });
+}
+
+fn fade_lesson(
+    mut commands: Commands,
+    mut labels: Query<(Entity, &mut LessonLabel, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut label, mut text) in labels.iter_mut() {
+        label.0.tick(time.delta());
+
+        if label.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(1.0 - label.0.fraction());
+        }
+    }
+}
+
+// with MoveAnnouncement::AnnounceMoves, the computer's last move is described just under the board, in its color
+#[derive(Component)]
+struct AnnouncementLabel(Timer);
+
+fn show_announcement(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    labels: Query<Entity, With<AnnouncementLabel>>,
+    view: LabelView,
+) {
+    let LabelView { screens, asset_server, game_speed } = view;
+
+    let Some((mark, cell)) = info.announcement.take() else { return; };
+
+    for entity in &labels {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some((screen, _)) = screens.iter().find(|(_, screen)| **screen == AppState::Game) else { return; };
+
+    commands.entity(screen).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                format!("I'll take {}", cell.friendly_name()),
+                TextStyle { font: asset_server.load("fonts/larabie.otf"), font_size: 30.0, color: mark.color() }
+            ).with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(80.0),
+                ..default()
+            }),
+            AnnouncementLabel(Timer::new(game_speed.scale(Duration::from_secs(2)), TimerMode::Once))
+        ));
+    });
+}
+
+fn fade_announcement(
+    mut commands: Commands,
+    mut labels: Query<(Entity, &mut AnnouncementLabel, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut label, mut text) in labels.iter_mut() {
+        label.0.tick(time.delta());
+
+        if label.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(1.0 - label.0.fraction());
+        }
+    }
+}
+
+fn fade_candidates(
+    mut commands: Commands,
+    mut labels: Query<(Entity, &mut CandidateLabel, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut label, mut text) in labels.iter_mut() {
+        label.0.tick(time.delta());
+
+        if label.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(1.0 - label.0.fraction());
+        }
+    }
+}
+
+// in debug builds, checks every change of GameState, so that a system setting the wrong next state is caught straight away
+fn check_transitions(mut transitions: EventReader<StateTransitionEvent<GameState>>) {
+    for transition in transitions.read() {
+        assert!(transition.before.can_become(&transition.after), "{:?} should never become {:?}", transition.before, transition.after);
+    }
+}
+
+// in debug builds, checks during every turn that the marks drawn on the board are exactly the marks in the Game
+// this runs in PostUpdate, after any marks spawned or despawned in Update have been
+fn check_marks(info: Res<StateInfo>, cells: Query<(&Cell, Option<&Children>)>, marks: Query<&Mark>) {
+    let describe = |mark: Option<Mark>| mark.map_or("nothing".to_string(), |mark| mark.to_string());
+
+    for (cell, children) in &cells {
+        let drawn = children.into_iter().flatten()
+            .filter_map(|child| marks.get(*child).ok())
+            .collect::<Vec<&Mark>>();
+
+        assert!(drawn.len() <= 1, "{} marks are drawn in {:?}", drawn.len(), cell);
+
+        let (drawn, played) = (drawn.first().map(|mark| **mark), info.game.get(*cell));
+        assert!(drawn == played, "{} is drawn in {:?}, but {} was played there", describe(drawn), cell, describe(played));
+    }
+
+    let (drawn, played) = (marks.iter().count(), info.game.history().len());
+    assert!(drawn == played, "{} marks are drawn, but {} moves have been made", drawn, played);
+}
+
+// with Countdown::CountDown, "3", "2", "1" are shown over the board for a second each when the game screen is opened
+// nobody (not even the computer) can move until the countdown is over
+#[derive(Component)]
+struct CountdownLabel(Timer);
+
+const COUNTDOWN_SECONDS: u64 = 3;
+
+fn start_countdown(mut commands: Commands, asset_server: Res<AssetServer>, countdown: Res<Countdown>) {
+    if *countdown == Countdown::NoCountdown { return; }
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(1),
+            ..default()
+        },
+        CountdownLabel(Timer::new(Duration::from_secs(COUNTDOWN_SECONDS), TimerMode::Once))
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            COUNTDOWN_SECONDS.to_string(),
+            TextStyle { font: asset_server.load("fonts/larabie.otf"), font_size: 300.0, color: Color::BLACK.with_a(0.6) }
+        ));
+    });
+}
+
+fn count_down(
+    mut commands: Commands,
+    mut labels: Query<(Entity, &mut CountdownLabel, &Children)>,
+    mut texts: Query<&mut Text>,
+    time: Res<Time>,
+) {
+    for (entity, mut label, children) in labels.iter_mut() {
+        label.0.tick(time.delta());
+
+        if label.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let remaining = (label.0.remaining_secs().ceil() as u64).to_string();
+
+        let mut iter = texts.iter_many_mut(children);
+
+        while let Some(mut text) = iter.fetch_next() {
+            // only touch the text when the number changes, so it isn't laid out again every frame
+            if text.sections[0].value != remaining {
+                text.sections[0].value = remaining.clone();
+            }
+        }
+    }
+}
+
+// run condition for anything which makes a move, which has to wait for the countdown (if any)
+fn countdown_over(labels: Query<(), With<CountdownLabel>>) -> bool {
+    labels.is_empty()
+}
+
+// the Theme chosen on the options screen only colors the game screen, and only from the start of the next game
+// the GridColor, if one is picked, colors the grid lines instead of the Theme
+// a selected ThemeFile is used in place of the Theme (see color_marks() for its X and O colors)
+fn apply_theme(
+    theme: Res<Theme>,
+    theme_files: Res<ThemeFiles>,
+    grid_color: Res<GridColor>,
+    mut clear_color: ResMut<ClearColor>,
+    mut cells: Query<&mut BorderColor, With<Cell>>,
+) {
+    clear_color.0 = theme_files.background(*theme);
+
+    for mut border in cells.iter_mut() {
+        border.0 = theme_files.grid(*theme, *grid_color);
+    }
+}
+
+// marks are drawn in their usual colors, unless a ThemeFile gives them others
+fn color_marks(mut marks: Query<(&Mark, &mut Text), Added<Mark>>, theme_files: Res<ThemeFiles>) {
+    if theme_files.selected().is_none() { return; }
+
+    for (mark, mut text) in marks.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = theme_files.mark(*mark);
+        }
+    }
+}
+
+fn remove_theme(mut clear_color: ResMut<ClearColor>) {
+    clear_color.0 = Theme::Classic.background();
+}
+
+// draws a big "X" or "O" in the middle of a cell
+fn spawn_mark(parent: &mut ChildBuilder, mark: Mark, font: Handle<Font>, scale: f32) {
+    parent.spawn((
+        TextBundle::from_section(
+            mark.to_string(),
+            TextStyle {
+                font_size: mark_font_size(scale),
+                font,
+                color: mark.color(),
+            }
+        ),
+        mark // tag the entity with the Mark Component
+    ));
+}
+
+// with MarkShading::ShadeComputer, in one-player games, the computer's marks are lighter than the human's, however they
+// were placed (by the computer, or loaded with the board, e.g. when a game is picked back up), but still red and blue
+fn shade_computer_marks(
+    mut marks: Query<(&Mark, &mut Text), Added<Mark>>,
+    mark_shading: Res<MarkShading>,
+    game_mode: Res<GameMode>,
+    human_mark: Res<HumanMark>,
+) {
+    if *mark_shading == MarkShading::PlainMarks || *game_mode != GameMode::OnePlayer { return; }
+
+    for (mark, mut text) in marks.iter_mut() {
+        if mark.is(*human_mark) { continue; }
+
+        for section in text.sections.iter_mut() {
+            section.style.color = section.style.color.with_a(0.55);
+        }
+    }
+}
+
+// text in a cell is sized as a fraction of the cell, so it always fits (with some room to spare), however big the board is
+const MARK_FILL: f32 = 0.8;
+const MOVE_NUMBER_FILL: f32 = 0.16;
+
+// the width or height of a cell (whichever is smaller, if they differ), in pixels, at the given BoardScale
+fn cell_size(scale: f32) -> f32 {
+    Column::SPACING.min(Row::SPACING) * scale
+}
+
+fn mark_font_size(scale: f32) -> f32 {
+    MARK_FILL * cell_size(scale)
+}
+
+fn move_number_font_size(scale: f32) -> f32 {
+    MOVE_NUMBER_FILL * cell_size(scale)
+}
+
+// tags the faint, not-yet-confirmed mark drawn after the first tap with TapToConfirm::TwoTaps
+#[derive(Component)]
+struct Preview;
+
+// tags the small move-order number drawn in the corner of a marked cell
+#[derive(Component)]
+struct MoveNumber;
+
+// draws the move number (1 for the first move, etc.) as a superscript
+fn spawn_move_number(parent: &mut ChildBuilder, number: usize, mark: Mark, font: Handle<Font>, scale: f32) {
+    parent.spawn((
+        TextBundle::from_section(
+            number.to_string(),
+            TextStyle {
+                font_size: move_number_font_size(scale),
+                font,
+                color: mark.color(),
+            }
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0 * scale),
+            right: Val::Px(25.0 * scale),
+            ..default()
+        }),
+        MoveNumber
+    ));
+}
+
+// with MoveNumbers::ShownAtEnd, every move of the final board is numbered once the game is over
+// puzzles are skipped, as the moves of their preset positions were never really played in that order
+fn number_moves_at_end(
+    mut commands: Commands,
+    info: Res<StateInfo>,
+    cells: Query<(Entity, &Cell)>,
+    move_numbers: Res<MoveNumbers>,
+    asset_server: Res<AssetServer>,
+    scale: Res<BoardScale>,
+) {
+    if *move_numbers != MoveNumbers::ShownAtEnd || info.puzzle.is_some() { return; }
+
+    for (index, cell) in info.game.history().iter().enumerate() {
+        let Some(mark) = info.game.get(*cell) else { continue; };
+        let Some((entity, _)) = cells.iter().find(|(_, c)| *c == cell) else { continue; };
+
+        commands.entity(entity).with_children(|parent| {
+            spawn_move_number(parent, index + 1, mark, asset_server.load("fonts/larabie.otf"), scale.0);
+        });
+    }
+}
+
+// a brief flash of color over a cell when a mark is placed in it, which shrinks and fades away
+#[derive(Component)]
+struct RippleEffect(Timer);
+
+fn spawn_ripple(parent: &mut ChildBuilder, mark: Mark, game_speed: GameSpeed) {
+    parent.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: mark.color().with_a(0.4).into(),
+            ..default()
+        },
+        RippleEffect(Timer::new(game_speed.scale(Duration::from_millis(200)), TimerMode::Once))
+    ));
+}
+
+fn animate_ripples(
+    mut commands: Commands,
+    mut ripples: Query<(Entity, &mut RippleEffect, &mut Style, &mut BackgroundColor)>,
+    time: Res<Time>,
+) {
+    for (entity, mut ripple, mut style, mut background) in ripples.iter_mut() {
+        ripple.0.tick(time.delta());
+
+        if ripple.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        // shrink towards the center of the cell while fading out
+        let remaining = 1.0 - ripple.0.fraction();
+        style.width = Val::Percent(100.0 * remaining);
+        style.height = Val::Percent(100.0 * remaining);
+        style.left = Val::Percent(50.0 * (1.0 - remaining));
+        style.top = Val::Percent(50.0 * (1.0 - remaining));
+        background.0.set_a(0.4 * remaining);
+    }
+}
+
+// a quick side-to-side shake of the whole board, to say "not now" when a click or tap is ignored
+#[derive(Component)]
+struct Shake(Timer);
+
+fn start_shake(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    boards: Query<Entity, With<Board>>,
+    board_shake: Res<BoardShake>,
+    game_speed: Res<GameSpeed>,
+) {
+    if !info.shake { return; }
+    info.shake = false;
+
+    if *board_shake == BoardShake::ShakeOff { return; }
+
+    for entity in &boards {
+        commands.entity(entity).insert(Shake(Timer::new(game_speed.scale(Duration::from_millis(300)), TimerMode::Once)));
+    }
+}
+
+// the board is moved with its Style, rather than its Transform, which is overwritten by the UI layout every frame
+fn animate_shake(
+    mut commands: Commands,
+    mut boards: Query<(Entity, &mut Shake, &mut Style)>,
+    scale: Res<BoardScale>,
+    time: Res<Time>,
+) {
+    for (entity, mut shake, mut style) in boards.iter_mut() {
+        shake.0.tick(time.delta());
+
+        if shake.0.finished() {
+            style.left = Val::Auto;
+            commands.entity(entity).remove::<Shake>();
+            continue;
+        }
+
+        // three wobbles, each smaller than the last
+        let progress = shake.0.fraction();
+        let offset = 10.0 * scale.0 * (1.0 - progress) * (progress * 6.0 * std::f32::consts::PI).sin();
+        style.left = Val::Px(offset);
+    }
+}
+
+// keeps BoardScale up to date as the window is resized (even when the board is not on screen)
+// UI sizes are already multiplied by the UiScale, so that's divided back out, to keep the board the same size in the window
+fn rescale_board(
+    mut resized: EventReader<WindowResized>,
+    mut scale: ResMut<BoardScale>,
+    ui_scale: Res<UiScale>,
+) {
+    // the board is three cells across, with a 25 pixel margin on each side, which is 800x800 with 250 pixel cells
+    let unscaled = |spacing: f32| 3.0 * spacing + 50.0;
+
+    if let Some(event) = resized.read().last() {
+        scale.0 = (event.width / unscaled(Column::SPACING)).min(event.height / unscaled(Row::SPACING)) / ui_scale.0;
+    }
+}
+
+// the marks (and previews) on the board, and the move numbers beside them, which are both drawn as Text
+type DrawnMarks = (Or<(With<Mark>, With<Preview>)>, Without<MoveNumber>);
+type DrawnMoveNumbers = (With<MoveNumber>, Without<Board>);
+
+// redraws the board (and everything on it) at the current BoardScale, so it keeps its shape, centered, and clickable
+fn resize_board(
+    scale: Res<BoardScale>,
+    mut boards: Query<&mut Style, With<Board>>,
+    mut marks: Query<&mut Text, DrawnMarks>,
+    mut move_numbers: Query<(&mut Text, &mut Style), DrawnMoveNumbers>,
+) {
+    if !scale.is_changed() { return; }
+
+    for mut style in boards.iter_mut() {
+        style.width = Val::Px(3.0 * Column::SPACING * scale.0);
+        style.height = Val::Px(3.0 * Row::SPACING * scale.0);
+    }
+
+    for mut text in marks.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.font_size = mark_font_size(scale.0);
+        }
+    }
+
+    for (mut text, mut style) in move_numbers.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.font_size = move_number_font_size(scale.0);
+        }
+        style.top = Val::Px(15.0 * scale.0);
+        style.right = Val::Px(25.0 * scale.0);
+    }
+}
+
+#[derive(Component)]
+enum GameOverButton {
+    PlayAgain,
+    NewSeries,
+    BackToMenu,
+    Puzzles
+}
+
+#[derive(Component)]
+struct GameOverOverlay {}
+
+// the settings for the end of a game, and what happens after it
+#[derive(SystemParam)]
+struct GameOverSettings<'w> {
+    match_length: Res<'w, MatchLength>,
+    side_swap: Res<'w, SideSwap>,
+    auto_restart: Res<'w, AutoRestart>,
+    result_emoji: Res<'w, ResultEmoji>,
+    result_overlay: Res<'w, ResultOverlay>,
+    tie_color: Res<'w, TieColor>,
+}
+
+// the score of this series (in two-player games), and of every game against the computer (in one-player games)
+#[derive(SystemParam)]
+struct Scores<'w> {
+    series: ResMut<'w, Series>,
+    stats: ResMut<'w, Stats>,
+}
+
+fn game_over(
+    mut commands: Commands,
+    info: Res<StateInfo>,
+    asset_server: Res<AssetServer>,
+    matchup: Matchup,
+    settings: GameOverSettings,
+    scores: Scores,
+    practice: Res<Practice>,
+) {
+    let Matchup { game_mode, human_mark, difficulty } = matchup;
+    let GameOverSettings { match_length, side_swap, result_emoji, result_overlay, tie_color, .. } = settings;
+    let Scores { mut series, mut stats } = scores;
+
+    let font = asset_server.load("fonts/larabie.otf");
+
+    // puzzles end after a single move, so show whether that move was right, rather than who won
+    if let Some(solved) = info.puzzle_solved {
+        let title = if solved {
+            vec![("Correct!".to_string(), Color::DARK_GREEN)]
+        } else {
+            vec![("Not quite...".to_string(), Color::BLACK)]
+        };
+
+        spawn_modal(&mut commands, font, title, vec![], vec![
+            ("more puzzles", Color::BLUE, GameOverButton::Puzzles),
+            ("back to menu", Color::RED, GameOverButton::BackToMenu),
+        ]).insert(GameOverOverlay {});
+
+        return;
+    }
+
+    if *game_mode == GameMode::TwoPlayers {
+        series.record(info.winner());
+    }
+
+    // anything else worth saying about how the game ended
+    let mut lines = vec![];
+
+    if let Some(resigned) = info.resigned {
+        lines.push(format!("{} resigned", resigned));
+    }
+
+    // keep track of how the human does against each difficulty (except when practising, like in rate_skill()), and of
+    // how often they play a game to the end
+    if *game_mode == GameMode::OnePlayer && practice.0.is_none() {
+        stats.record(*difficulty, human_result(&info, *human_mark));
+
+        let already_earned = stats.never_gave_up();
+        stats.record_ending(info.resigned.is_some());
+
+        if stats.never_gave_up() && !already_earned {
+            info!("earned the \"never give up\" achievement");
+            lines.push(format!("achievement: never give up ({} games without resigning)", NEVER_GIVE_UP));
+        }
+
+        if let Err(error) = stats.save(STATS_FILE) {
+            warn!("could not save stats to {}: {}", STATS_FILE, error);
+        }
+    }
+
+    // the last game of a best-of series gets a summary of the whole series, rather than just this game
+    if *game_mode == GameMode::TwoPlayers && match_length.games() > 1 && series.decided(*match_length) {
+        let result = match series.leader() {
+            None => "the match is a tie".to_string(),
+            Some(player) => format!("player {} ({}) wins the match", player + 1, series.mark(player)),
+        };
+
+        let title = vec![("Match over!".to_string(), Color::BLACK)];
+
+        let lines = vec![
+            result,
+            format!("final score: {} - {}", series.wins[0], series.wins[1]),
+        ];
+
+        spawn_modal(&mut commands, font, title, lines, vec![
+            ("new series", Color::BLUE, GameOverButton::NewSeries),
+            ("back to menu", Color::RED, GameOverButton::BackToMenu),
+        ]).insert(GameOverOverlay {});
+
+        return;
+    }
+
+    let title = match info.winner() {
+        None => vec![("It's a tie!".to_string(), tie_color.color())],
+        Some(winner) => vec![(winner.to_string(), winner.color()), (" wins!".to_string(), Color::BLACK)],
+    };
+
+    // make it obvious that the players will be changing marks in the next game
+    let play_again = match (*game_mode, *side_swap) {
+        (GameMode::TwoPlayers, SideSwap::Swap) => "swap sides",
+        _ => "play again"
+    };
+
+    // larabie.otf has no emoji, so these are drawn in DejaVu Sans (see fonts.rs), which has no party popper or handshake
+    let banner = match (*result_emoji, info.winner()) {
+        (ResultEmoji::NoEmoji, _) => None,
+        (ResultEmoji::ShowEmoji, None) => Some("\u{263A}"), // ☺
+        (ResultEmoji::ShowEmoji, Some(winner)) if *game_mode == GameMode::OnePlayer && !winner.is(*human_mark) => Some("\u{1F61E}"), // 😞
+        (ResultEmoji::ShowEmoji, Some(_)) => Some("\u{1F600}"), // 😀
+    };
+
+    if *result_overlay == ResultOverlay::Toast {
+        let mut title = title;
+
+        if let Some(emoji) = banner {
+            title.insert(0, (format!("{} ", emoji), Color::BLACK));
+        }
+
+        spawn_toast(&mut commands, font, title, vec![
+            (play_again, Color::BLUE, GameOverButton::PlayAgain),
+            ("menu", Color::RED, GameOverButton::BackToMenu),
+        ]).insert(GameOverOverlay {});
+
+        return;
+    }
+
+    let banner = banner.map(|emoji| {
+        commands.spawn(TextBundle::from_section(emoji, TextStyle { font: font.clone(), font_size: 150.0, color: Color::BLACK })).id()
+    });
+
+    let mut modal = spawn_modal(&mut commands, font, title, lines, vec![
+        (play_again, Color::BLUE, GameOverButton::PlayAgain),
+        ("back to menu", Color::RED, GameOverButton::BackToMenu),
+    ]);
+
+    modal.insert(GameOverOverlay {});
+
+    // the modal lays out its children in a centered column, so the banner goes above the inner window
+    if let Some(banner) = banner {
+        modal.insert_children(0, &[banner]);
+    }
+}
+
+// with ResultOverlay::Toast, the result is a small banner along the top of the screen, rather than an overlay, so the
+// board stays in full view
+//
+// the title and buttons are given as for spawn_modal(), but drawn smaller, all on one row
+fn spawn_toast<'a, T: Component>(
+    commands: &'a mut Commands,
+    font: Handle<Font>,
+    title: Vec<(String, Color)>,
+    buttons: Vec<(&str, Color, T)>,
+) -> EntityCommands<'a> {
+    let mut toast = commands.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            top: Val::ZERO,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(20.0),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        background_color: Color::rgba(1.0, 1.0, 1.0, 0.85).into(),
+        z_index: ZIndex::Global(1),
+        ..default()
+    });
+
+    toast.with_children(|parent| {
+        let sections = title.into_iter()
+            .map(|(text, color)| TextSection::new(text, TextStyle { font: font.clone(), font_size: 40.0, color }));
+
+        parent.spawn(TextBundle::from_sections(sections));
+
+        for (text, color, marker) in buttons {
+            parent.spawn((
+                ButtonBundle {
+                    style: Style { padding: UiRect::all(Val::Px(5.0)), ..default() },
+                    background_color: Color::NONE.into(),
+                    ..default()
+                },
+                marker
+            )).with_children(|parent| {
+                parent.spawn(TextBundle::from_section(text, TextStyle { font: font.clone(), font_size: 30.0, color }));
+            });
+        }
+    });
+
+    toast
+}
+
+// with ResultOverlay::SeeThrough, the game over overlay's inner window is faint enough to read the final board through
+fn see_through_overlay(
+    result_overlay: Res<ResultOverlay>,
+    overlays: Query<&Children, Added<GameOverOverlay>>,
+    mut windows: Query<&mut BackgroundColor, With<ModalWindow>>,
+) {
+    if *result_overlay == ResultOverlay::Solid { return; }
+
+    for children in &overlays {
+        let mut iter = windows.iter_many_mut(children);
+
+        while let Some(mut background) = iter.fetch_next() {
+            background.0.set_a(0.35);
+        }
+    }
+}
+
+// tints the cells of the winning line in the winner's color, so it's clear how the game was won
+fn highlight_winning_line(
+    info: Res<StateInfo>,
+    winning_line: Res<WinningLine>,
+    mut cells: Query<(&Cell, &mut BackgroundColor)>,
+) {
+    if *winning_line == WinningLine::NotHighlighted { return; }
+
+    let Some((winner, _)) = info.game.winner() else { return; };
+
+    // a single move can complete two lines at once, so highlight every line, not just the one winner() found first
+    let lines = info.game.completed_lines(winner);
+
+    for (cell, mut background) in cells.iter_mut() {
+        if lines.iter().any(|line| line.cells().contains(cell)) {
+            *background = winner.color().with_a(0.2).into();
+        }
+    }
+}
+
+// faintly highlights every line (row, column, or diagonal) through the cell under the cursor
+fn hint_lines(
+    windows: Query<&Window>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut cells: Query<(&Cell, &mut BackgroundColor)>,
+    line_hints: Res<LineHints>,
+    scale: Res<BoardScale>,
+) {
+    if *line_hints == LineHints::NoHints { return; }
+
+    let (camera, camera_transform) = cameras.get_single().expect("expected exactly one camera");
+
+    let hovered = windows.get_single().ok()
+        .and_then(|window| window.cursor_position())
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        .and_then(|world| Cell::hit(world / scale.0));
+
+    let hinted = Line::variants().into_iter()
+        .filter(|line| hovered.is_some_and(|hovered| line.cells().contains(&hovered)))
+        .flat_map(|line| line.cells())
+        .collect::<Vec<Cell>>();
+
+    for (cell, mut background) in cells.iter_mut() {
+        let color = if hinted.contains(cell) { Color::rgba(0.0, 0.0, 0.0, 0.05) } else { Color::NONE };
+
+        // only touch cells which need to change, so this doesn't flag every cell as changed every frame
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+fn clear_highlights(mut cells: Query<&mut BackgroundColor, With<Cell>>) {
+    for mut background in cells.iter_mut() {
+        *background = Color::NONE.into();
+    }
+}
+
+// with Overtime::SuddenDeath, a tie clears the board, and the players swap marks (so whoever went second now goes first)
+// in one-player games, this is just like choosing the other mark on the menu, but only until the game is over (see
+// MenuHumanMark)
+fn start_overtime(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    mut human_mark: ResMut<HumanMark>,
+    mut series: ResMut<Series>,
+    game_mode: Res<GameMode>,
+    leftovers: Query<Entity, OnBoard>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    for entity in &leftovers {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    match *game_mode {
+        GameMode::OnePlayer => {
+            *human_mark = match *human_mark {
+                HumanMark::HumanX => HumanMark::HumanO,
+                HumanMark::HumanO => HumanMark::HumanX,
+                HumanMark::HumanRandom => HumanMark::HumanRandom, // already resolved in start_game()
+            }
+        }
+        _ => series.player_one = series.mark(1),
+    }
+
+    let overtime_rounds = info.overtime_rounds + 1;
+    info!("sudden death: starting overtime round {}", overtime_rounds);
+
+    info.reset();
+    info.overtime_rounds = overtime_rounds;
+    next_game_state.set(GameState::XTurn);
+}
+
+fn update_overtime_label(mut labels: Query<&mut Text, With<OvertimeLabel>>, info: Res<StateInfo>) {
+    if !info.is_changed() { return; }
+
+    let value = match info.overtime_rounds {
+        0 => String::new(),
+        rounds => format!("sudden death: overtime round {}", rounds),
+    };
+
+    for mut text in labels.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+fn update_cells_left_label(mut labels: Query<&mut Text, With<CellsLeftLabel>>, info: Res<StateInfo>, cells_left: Res<CellsLeft>) {
+    if !info.is_changed() && !cells_left.is_changed() { return; }
+
+    let value = match *cells_left {
+        CellsLeft::HideCellsLeft => String::new(),
+        CellsLeft::ShowCellsLeft => format!("{} left", Cell::CARDINALITY - info.game.move_count()),
+    };
+
+    for mut text in labels.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+// before the game over overlay is shown, the winning move is replayed: the mark grows back into its cell, then the
+// winning line is drawn one cell at a time (this is skipped on GameSpeed::Fast)
+fn start_instant_replay(mut info: ResMut<StateInfo>, game_speed: Res<GameSpeed>) {
+    info.replay_time = Timer::new(game_speed.scale(Duration::from_millis(1000)), TimerMode::Once);
+}
+
+fn instant_replay(
+    mut info: ResMut<StateInfo>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut cells: Query<(Entity, &Cell, &mut BackgroundColor)>,
+    mut marks: Query<(&Parent, &mut Text), With<Mark>>,
+    scale: Res<BoardScale>,
+    time: Res<Time>,
+) {
+    info.replay_time.tick(time.delta());
+    let progress = info.replay_time.fraction();
+
+    let (Some((winner, line)), Some(last)) = (info.game.winner(), info.game.history().last()) else {
+        next_game_state.set(GameState::GameOver);
+        return;
+    };
+
+    // the first half of the replay grows the winning mark...
+    let growth = (progress * 2.0).min(1.0);
+
+    for (entity, cell, mut background) in cells.iter_mut() {
+        if cell == last {
+            for (_, mut text) in marks.iter_mut().filter(|(parent, _)| parent.get() == entity) {
+                for section in text.sections.iter_mut() {
+                    section.style.font_size = mark_font_size(scale.0) * growth;
+                }
+            }
+        }
+
+        // ...and the second half draws the line through it
+        if let Some(index) = line.cells().iter().position(|c| c == cell) {
+            if progress >= 0.5 + index as f32 / 6.0 {
+                *background = winner.color().with_a(0.2).into();
+            }
+        }
+    }
+
+    if info.replay_time.finished() {
+        next_game_state.set(GameState::GameOver);
+    }
+}
 
-                let unique_marks = marks.iter().cloned()
-                    .collect::<HashSet<Mark>>();
+// with AutoRestart::RestartAutomatically, how long the game over overlay is shown before a new game starts
+fn start_restart_timer(mut info: ResMut<StateInfo>, game_speed: Res<GameSpeed>) {
+    info.restart_time = Timer::new(game_speed.scale(Duration::from_secs(5)), TimerMode::Once);
+}
 
-                if marks.len() == 3 && unique_marks.len() == 1 {
-                    return Some((*marks.get(0).unwrap(), line))
-                };
-            }
+// the buttons on the game over overlay, which can be clicked, or "pressed" with the keyboard
+#[derive(SystemParam)]
+struct GameOverInput<'w, 's> {
+    buttons: Query<'w, 's, (&'static Interaction, &'static GameOverButton), Changed<Interaction>>,
+    all_buttons: Query<'w, 's, &'static GameOverButton>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+}
 
-            None
-        }
+// the next AppState and GameState, for systems which can change both
+#[derive(SystemParam)]
+struct NextStates<'w> {
+    app: ResMut<'w, NextState<AppState>>,
+    game: ResMut<'w, NextState<GameState>>,
+}
 
-        // behind a getter so the user cannot mutate this field directly
-        pub fn winner(&self) -> Option<(Mark, Line)> {
-            self.winner
-        }
+fn game_over_buttons(
+    input: GameOverInput,
+    next_states: NextStates,
+    mut info: ResMut<StateInfo>,
+    mut series: ResMut<Series>,
+    settings: GameOverSettings,
+    mut side: HumanSide,
+    time: Res<Time>,
+) {
+    let GameOverInput { buttons, all_buttons, keys } = input;
+    let NextStates { app: mut next_app_state, game: mut next_game_state } = next_states;
+    let GameOverSettings { match_length, side_swap, auto_restart, .. } = settings;
+
+    let pressed = buttons.iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button);
+
+    // enter or space "presses" whichever button goes on to the next game (or puzzle), and escape goes back to the menu
+    let typed = if keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]) {
+        all_buttons.iter().find(|button| matches!(button, GameOverButton::PlayAgain | GameOverButton::NewSeries | GameOverButton::Puzzles))
+    } else if keys.just_pressed(KeyCode::Escape) {
+        all_buttons.iter().find(|button| matches!(button, GameOverButton::BackToMenu))
+    } else {
+        None
+    };
 
-        // behind a getter so the user cannot mutate this field directly
-        pub fn over(&self) -> bool {
-            self.over
-        }
+    // when the timer runs out, "press" whichever button starts the next game (puzzles don't restart by themselves)
+    let automatic = match *auto_restart {
+        AutoRestart::RestartManually => None,
+        AutoRestart::RestartAutomatically => {
+            info.restart_time.tick(time.delta());
 
-        // behind a getter so the user cannot access / mutate marks directly
-        pub fn get(&self, cell: Cell) -> Option<Mark> {
-            self.marks.get(&cell).cloned().flatten()
+            if info.restart_time.just_finished() {
+                all_buttons.iter().find(|button| matches!(button, GameOverButton::PlayAgain | GameOverButton::NewSeries))
+            } else {
+                None
+            }
         }
+    };
 
-        // behind a setter so we can recalculate the winner immediately
-        pub fn set(&mut self, cell: Cell, mark: Mark) {
-            self.marks.insert(cell, Some(mark));
-            self.winner = Game::determine_winner(&self.marks);
-            self.over = self.winner.is_some() || self.marks.len() == 9;
+    if let Some(button) = pressed.or(typed).or(automatic) {
+        match button {
+            GameOverButton::PlayAgain => {
+                if series.decided(*match_length) {
+                    *series = Series { player_one: series.player_one, ..default() };
+                }
+                if *side_swap == SideSwap::Swap {
+                    series.player_one = series.mark(1);
+                }
+                side.resolve();
+                info.reset();
+                next_game_state.set(GameState::XTurn);
+            }
+            GameOverButton::NewSeries => {
+                *series = Series::default();
+                side.resolve();
+                info.reset();
+                next_game_state.set(GameState::XTurn);
+            }
+            // the series score is lost when leaving the game screen, so ask first, unless the series is already over
+            GameOverButton::BackToMenu if series.wins != [0, 0] && !series.decided(*match_length) => {
+                info.confirm_leave = true;
+            }
+            GameOverButton::BackToMenu => {
+                next_game_state.set(GameState::GameNotInProgress);
+                next_app_state.set(AppState::Menu);
+            }
+            GameOverButton::Puzzles => {
+                next_game_state.set(GameState::GameNotInProgress);
+                next_app_state.set(AppState::Puzzles);
+            }
         }
     }
 }
 
-#[derive(Resource, Default)]
-struct StateInfo {
-    game: game::Game,
-    current_player: Mark,
-    computer_thinking_time: Timer
+#[derive(Component)]
+enum LeaveButton {
+    Leave,
+    Stay,
 }
 
-pub fn plugin(app: &mut App) {
-    app
-        .insert_resource(HumanMark::default())
-        .insert_resource(StateInfo::default())
-        .add_systems(OnEnter(AppState::Game), start_game)
-        .init_state::<GameState>()
-        .add_systems(OnEnter(GameState::XTurn), start_x_turn)
-        .add_systems(Update, capture_input.run_if(in_state(GameState::XTurn)))
-        .add_systems(OnEnter(GameState::OTurn), start_o_turn)
-        .add_systems(Update, capture_input.run_if(in_state(GameState::OTurn)))
-        .add_systems(OnEnter(GameState::GameOver), game_over)
-        .add_systems(Update, game_over_buttons.run_if(in_state(GameState::GameOver)))
-        .add_systems(OnExit(GameState::GameOver), clear_entities::<Mark>)
-        .add_systems(OnExit(GameState::GameOver), clear_entities::<GameOverOverlay>)
-        .add_systems(OnExit(AppState::Game), clear_entities::<AppState>)
-        .add_systems(OnExit(AppState::Game), clear_entities::<GameOverOverlay>);
-}
+// tags the prompt asking whether to go back to the menu in the middle of a series
+#[derive(Component)]
+struct LeaveConfirmation;
 
-fn start_x_turn(mut info: ResMut<StateInfo>) {
-    info.current_player = Mark::X
+// run condition for the game over buttons, which are paused while the prompt is shown
+fn leave_unconfirmed(prompts: Query<(), With<LeaveConfirmation>>) -> bool {
+    prompts.is_empty()
 }
 
-fn start_o_turn(mut info: ResMut<StateInfo>) {
-    info.current_player = Mark::O
+fn confirm_leave(
+    mut commands: Commands,
+    mut info: ResMut<StateInfo>,
+    series: Res<Series>,
+    asset_server: Res<AssetServer>,
+) {
+    if !std::mem::take(&mut info.confirm_leave) { return; }
+
+    spawn_modal(
+        &mut commands,
+        asset_server.load("fonts/larabie.otf"),
+        vec![("Leave?".to_string(), Color::BLACK)],
+        vec![format!("the score ({} - {}) won't be kept", series.wins[0], series.wins[1])],
+        vec![
+            ("keep playing", Color::BLUE, LeaveButton::Stay),
+            ("back to menu", Color::RED, LeaveButton::Leave),
+        ],
+    ).insert((
+        LeaveConfirmation,
+        Dismissable, // escape is the same as "keep playing"
+        FocusPolicy::Block, // stop the game over buttons underneath the prompt from being pressed
+        ZIndex::Global(2) // draw above the game over overlay
+    ));
 }
 
-fn start_game(
+// enter confirms, like on the game over overlay (escape cancels, see dismiss_modal())
+fn leave_buttons(
     mut commands: Commands,
-    mut next_game_state: ResMut<NextState<GameState>>
+    buttons: Query<(&Interaction, &LeaveButton), Changed<Interaction>>,
+    prompts: Query<Entity, With<LeaveConfirmation>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    keys: Res<ButtonInput<KeyCode>>,
 ) {
+    if prompts.is_empty() { return; }
 
-    next_game_state.set(GameState::XTurn);
+    let pressed = buttons.iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button);
 
-    const GRID_SPACING: f32 = 250.0;
+    let leave = match pressed {
+        Some(LeaveButton::Leave) => true,
+        Some(LeaveButton::Stay) => false,
+        None if keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) => true,
+        None => return,
+    };
 
-    fn cell<'a>(parent: &'a mut ChildBuilder, cell: Cell, border: UiRect) -> EntityCommands<'a> {
-        parent.spawn((
-            NodeBundle {
-                style: Style {
-                    display: Display::Grid,
-                    grid_row: GridPlacement::start((-cell.row().position() + 2) as i16),
-                    grid_column: GridPlacement::start((cell.column().position() + 2) as i16),
-                    justify_items: JustifyItems::Center,
-                    align_items: AlignItems::Center,
-                    border,
-                    ..default()
-                },
-                border_color: Color::BLACK.into(),
-                ..default()
-            },
-            cell
-        ))
+    for entity in &prompts {
+        commands.entity(entity).despawn_recursive();
     }
 
-    draw_screen(&mut commands, AppState::Game).with_children(|parent| {
-        parent.spawn(NodeBundle {
-            style: Style {
-                display: Display::Grid,
-                grid_template_rows: vec![GridTrack::flex(1.0), GridTrack::flex(1.0), GridTrack::flex(1.0)],
-                grid_template_columns: vec![GridTrack::flex(1.0), GridTrack::flex(1.0), GridTrack::flex(1.0)],
-                width: Val::Px(3.0 * GRID_SPACING),
-                height: Val::Px(3.0 * GRID_SPACING),
-                ..default()
-            },
-            ..default()
-        }).with_children(|parent| {
-            const NONE: Val = Val::ZERO;
-            const THIN: Val = Val::Px(6.0);
-
-            // top row
-            cell(parent, Cell::TopLeft, UiRect::new(NONE, THIN, NONE, THIN));
-            cell(parent, Cell::TopMiddle, UiRect::new(NONE, NONE, NONE, THIN));
-            cell(parent, Cell::TopRight, UiRect::new(THIN, NONE, NONE, THIN));
-
-            // middle row
-            cell(parent, Cell::MiddleLeft, UiRect::new(NONE, THIN, NONE, NONE));
-            cell(parent, Cell::MiddleMiddle, UiRect::new(NONE, NONE, NONE, NONE));
-            cell(parent, Cell::MiddleRight, UiRect::new(THIN, NONE, NONE, NONE));
-
-            // bottom row
-            cell(parent, Cell::BottomLeft, UiRect::new(NONE, THIN, THIN, NONE));
-            cell(parent, Cell::BottomMiddle, UiRect::new(NONE, NONE, THIN, NONE));
-            cell(parent, Cell::BottomRight, UiRect::new(THIN, NONE, THIN, NONE));
-        });
-    });
-}
-
-#[derive(Component)]
-enum GameOverButton {
-    PlayAgain,
-    BackToMenu
+    if leave {
+        next_game_state.set(GameState::GameNotInProgress);
+        next_app_state.set(AppState::Menu);
+    }
 }
 
-#[derive(Component)]
-struct GameOverOverlay {}
-
-fn game_over(
-    mut commands: Commands,
-    info: Res<StateInfo>,
-    asset_server: Res<AssetServer>
+fn update_series_label(
+    mut labels: Query<&mut Text, With<SeriesLabel>>,
+    series: Res<Series>,
+    asset_server: Res<AssetServer>,
 ) {
-    let font = asset_server.load("fonts/larabie.otf");
+    if !series.is_changed() { return; }
 
-    // entire screen
-    commands.spawn((
-        NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                position_type: PositionType::Absolute,
-                left: Val::ZERO,
-                top: Val::ZERO,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::Column,
-                ..default()
-            },
-            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
-            z_index: ZIndex::Global(1),
-            ..default()
-        },
-        GameOverOverlay {}
-    )).with_children(|parent| {
+    for mut text in labels.iter_mut() {
+        text.sections = series.label(asset_server.load("fonts/larabie.otf"));
+    }
+}
 
-        // inner window
-        parent.spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(61.8),
-                flex_direction: FlexDirection::Column,
-                ..default()
-            },
-            background_color: Color::rgba(1.0, 1.0, 1.0, 0.85).into(),
-            ..default()
-        }).with_children(|parent| {
+// whether the window has focus, so that the computer's thinking time can be paused while it doesn't
+#[derive(Resource)]
+struct WindowFocus(bool);
 
-            // top row
-            parent.spawn(NodeBundle {
-                style: Style {
-                    justify_content: JustifyContent::Center,
-                    padding: UiRect::all(Val::Px(10.0)),
-                    ..default()
-                },
-                ..default()
-            }).with_children(|parent| {
-                fn spawn_text(
-                    parent: &mut ChildBuilder,
-                    text: impl Into<String>,
-                    font: Handle<Font>,
-                    text_color: Color
-                ) {
-                    parent.spawn(TextBundle::from_section(
-                        text,
-                        TextStyle {
-                            color: text_color,
-                            font_size: 75.0,
-                            font: font.clone(),
-                            ..default()
-                        }
-                    ));
-                }
+fn track_window_focus(mut events: EventReader<WindowFocused>, mut focus: ResMut<WindowFocus>) {
+    if let Some(event) = events.read().last() {
+        focus.0 = event.focused;
+    }
+}
 
-                match info.game.winner() {
-                    None => {
-                        spawn_text(parent, "It's a tie!", font.clone(), Color::BLACK);
-                    }
-                    Some((winner, _)) => {
-                        spawn_text(parent, format!("{}", winner.to_string()), font.clone(), winner.color());
-                        spawn_text(parent, " wins!", font.clone(), Color::BLACK);
-                    }
-                }
-            });
+// anything which moves by itself, so needs the screen to keep updating (see keep_awake())
+type Animated = Or<(With<RippleEffect>, With<Shake>, With<LessonLabel>, With<CountdownLabel>, With<CandidateLabel>, With<TurnPromptLabel>, With<AnnouncementLabel>, With<Hop>)>;
 
-            fn button(parent: &mut ChildBuilder, text: impl Into<String>, color: Color, marker: GameOverButton, font: Handle<Font>) {
-                parent.spawn((
-                    ButtonBundle {
-                        style: Style {
-                            justify_content: JustifyContent::Center,
-                            padding: UiRect::all(Val::Px(10.0)),
-                            ..default()
-                        },
-                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
-                        ..default()
-                    },
-                    marker
-                )).with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        text,
-                        TextStyle {
-                            color,
-                            font_size: 60.0,
-                            font: font.clone(),
-                            ..default()
-                        }
-                    ));
-                });
-            }
+// a change of GameState or AppState which has been asked for, but hasn't happened yet
+#[derive(SystemParam)]
+struct PendingStates<'w> {
+    game: Res<'w, NextState<GameState>>,
+    app: Res<'w, NextState<AppState>>,
+}
 
-            button(parent, "play again", Color::BLUE, GameOverButton::PlayAgain, font.clone());
-            button(parent, "back to menu", Color::RED, GameOverButton::BackToMenu, font.clone());
-        });
-    });
+impl PendingStates<'_> {
+    fn any(&self) -> bool {
+        self.game.0.is_some() || self.app.0.is_some()
+    }
 }
 
-fn game_over_buttons(
-    buttons: Query<(&Interaction, &GameOverButton), (Changed<Interaction>, With<Button>)>,
-    mut next_app_state: ResMut<NextState<AppState>>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-    mut info: ResMut<StateInfo>,
+// with PowerSaving::LowPower, asks for another update straight away whenever the game screen can't wait for input, i.e.
+// while the computer is thinking, while anything is animating or counting down, and while a state change is pending
+fn keep_awake(
+    info: Res<StateInfo>,
+    matchup: Matchup,
+    auto_restart: Res<AutoRestart>,
+    game_state: Res<State<GameState>>,
+    pending: PendingStates,
+    moving: Query<(), Animated>,
+    mut redraw: EventWriter<RequestRedraw>,
 ) {
-    for (interaction, button) in buttons.iter() {
-        if let Interaction::Pressed = interaction {
-            match button {
-                GameOverButton::PlayAgain => {
-                    *info = StateInfo::default();
-                    next_game_state.set(GameState::XTurn);
-                }
-                GameOverButton::BackToMenu => {
-                    *info = StateInfo::default();
-                    next_game_state.set(GameState::GameNotInProgress);
-                    next_app_state.set(AppState::Menu);
-                }
-            }
-        }
+    let Matchup { game_mode, human_mark, .. } = matchup;
+
+    let busy = match game_state.get() {
+        GameState::XTurn | GameState::OTurn => *game_mode == GameMode::OnePlayer && !info.current_player.is(*human_mark),
+        GameState::InstantReplay | GameState::Overtime => true,
+        GameState::GameOver => *auto_restart == AutoRestart::RestartAutomatically,
+        GameState::GameNotInProgress => false,
+    };
+
+    if busy || !moving.is_empty() || pending.any() {
+        redraw.send(RequestRedraw);
     }
 }
 
+// touch input is tracked separately from mouse input, because it might need to be confirmed with a second tap
+enum UserInput {
+    Touch(Cell),
+    Click(Cell),
+}
+
 fn capture_user_input(
     windows: Query<&Window>,
     cameras: Query<(&Camera, &GlobalTransform)>,
     touch_input: Res<Touches>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
-) -> Option<Cell> {
+    scale: &BoardScale,
+    place_on: PlaceOn,
+    pressed_cell: &mut Option<Cell>,
+) -> Option<UserInput> {
 
     // expect() because we spawn only a single Camera2dBundle and expect Bevy to be able to provide it to us
     let (camera, camera_transform) = cameras.get_single().expect("expected exactly one camera");
@@ -480,149 +2256,226 @@ fn capture_user_input(
     // get touch input from users on mobile
     let maybe_touch_coordinates: Option<Vec2> =
         touch_input.iter()
-            .filter(|finger| touch_input.just_pressed(finger.id()))
-            .next()
+            .find(|finger| touch_input.just_pressed(finger.id()))
             .map(|finger| finger.position());
 
-    // get mouse input from users on desktop
-    let maybe_click_coordinates: Option<Vec2> =
-        windows.get_single().iter()
-            .filter(|_| mouse_button_input.just_pressed(MouseButton::Left))
-            .next()
-            .and_then(|window| window.cursor_position());
-
-    maybe_touch_coordinates.or(maybe_click_coordinates)
-        .and_then(|window_coordinates| camera.viewport_to_world_2d(camera_transform, window_coordinates))
-        .and_then(|world_coordinates| Cell::hit(world_coordinates))
-}
-
-fn generate_computer_input(game: &game::Game, computer: Mark, difficulty: Difficulty) -> Cell {
-
-    // weight cells based on their advantage to the computer and their disadvantage to the human
-    //
-    //   1. +20 for any cell which lets the computer win this turn
-    //   2. +10 for any cell which blocks a human win this turn
-    //   3. +2 for the middle-middle space
-    //   4. +1 for any corner space
-    //
-    // ...then, just pick the cell with the highest weight, after filtering out already-occupied cells
-
-    let mut weights: [i8;9] = [0, 0, 0, 0, 0, 0, 0, 0, 0];
-
-    // scale weights based on difficulty, so the computer picks non-optimal moves
-
-    let scale = match difficulty {
-        Difficulty::Easy => -1, // purposefully pick the worst possible moves
-        Difficulty::Medium => {
-            // randomly pick best-possible and worst-possible moves
-            let mut rng = thread_rng();
-            *[-1, 1].choose(&mut rng).expect("array is non-empty, so we should always get a value")
-        },
-        Difficulty::Hard => 1, // pick the best possible moves
-    };
+    let cursor_coordinates = windows.get_single().ok().and_then(|window| window.cursor_position());
 
-    fn index(cell: Cell) -> usize {
-        match cell {
-            Cell::TopLeft => 0,
-            Cell::TopMiddle => 1,
-            Cell::TopRight => 2,
-            Cell::MiddleLeft => 3,
-            Cell::MiddleMiddle => 4,
-            Cell::MiddleRight => 5,
-            Cell::BottomLeft => 6,
-            Cell::BottomMiddle => 7,
-            Cell::BottomRight => 8,
-        }
-    }
+    let hit = |window_coordinates: Vec2| {
+        camera.viewport_to_world_2d(camera_transform, window_coordinates)
+            .and_then(|world_coordinates| Cell::hit(world_coordinates / scale.0)) // Cell::hit() assumes an unscaled board
+    };
 
-    Line::variants().iter().for_each(|line| {
-        let cells_and_marks = line.cells().map(|cell| (cell, game.get(cell)));
+    // get mouse input from users on desktop
+    let maybe_click = match place_on {
+        PlaceOn::PlaceOnPress if mouse_button_input.just_pressed(MouseButton::Left) => cursor_coordinates.and_then(hit),
+        PlaceOn::PlaceOnPress => None,
+
+        // only a press and release over the same cell counts, so dragging off the cell before releasing cancels the click
+        PlaceOn::PlaceOnRelease => {
+            if mouse_button_input.just_pressed(MouseButton::Left) {
+                *pressed_cell = cursor_coordinates.and_then(hit);
+            }
 
-        // case (1)
-        match cells_and_marks {
-            [(_, Some(a)), (_, Some(b)), (cell, None)] if a == b && b == computer => weights[index(cell)] += 20 * scale,
-            [(_, Some(a)), (cell, None), (_, Some(b))] if a == b && b == computer => weights[index(cell)] += 20 * scale,
-            [(cell, None), (_, Some(a)), (_, Some(b))] if a == b && b == computer => weights[index(cell)] += 20 * scale,
-            _ => {}
+            if mouse_button_input.just_released(MouseButton::Left) {
+                let pressed = pressed_cell.take();
+                cursor_coordinates.and_then(hit).filter(|released| Some(*released) == pressed)
+            } else {
+                None
+            }
         }
+    };
 
-        // case (2)
-        match cells_and_marks {
-            [(_, Some(a)), (_, Some(b)), (cell, None)] if a == b && b != computer => weights[index(cell)] += 10 * scale,
-            [(_, Some(a)), (cell, None), (_, Some(b))] if a == b && b != computer => weights[index(cell)] += 10 * scale,
-            [(cell, None), (_, Some(a)), (_, Some(b))] if a == b && b != computer => weights[index(cell)] += 10 * scale,
-            _ => {}
-        }
+    maybe_touch_coordinates.and_then(hit).map(UserInput::Touch)
+        .or(maybe_click.map(UserInput::Click))
+}
 
-        // case (3)
-        match cells_and_marks {
-            [_, (cell, None), _] if cell == Cell::MiddleMiddle => weights[index(cell)] += 2 * scale,
-            _ => {}
-        }
+// the settings which affect how a game is played, bundled together to keep capture_input()'s parameter list manageable
+#[derive(SystemParam)]
+struct GameSettings<'w> {
+    game_mode: Res<'w, GameMode>,
+    human_mark: Res<'w, HumanMark>,
+    difficulty: Res<'w, Difficulty>,
+    move_numbers: Res<'w, MoveNumbers>,
+    game_speed: Res<'w, GameSpeed>,
+    tap_to_confirm: Res<'w, TapToConfirm>,
+    ripple: Res<'w, Ripple>,
+    overtime: Res<'w, Overtime>,
+    place_on: Res<'w, PlaceOn>,
+    forgiveness: Res<'w, Forgiveness>,
+    tie_break: Res<'w, TieBreak>,
+    center_bonus: Res<'w, CenterBonus>,
+    first_move: Res<'w, FirstMove>,
+    play_style: Res<'w, PlayStyle>,
+    threat_priority: Res<'w, ThreatPriority>,
+    sound: Res<'w, Sound>,
+    placement_pitch: Res<'w, PlacementPitch>,
+    occupied_cell: Res<'w, OccupiedCell>,
+    teaching: Res<'w, Teaching>,
+    move_announcement: Res<'w, MoveAnnouncement>,
+    stats: Res<'w, Stats>, // for the human's losing streak, with Forgiveness::Forgiving
+    practice: Res<'w, Practice>,
+}
 
-        // case (4)
-        match cells_and_marks {
-            [(c1, None), _, (c2, None)] if c1.is_corner() => {
-                weights[index(c1)] += 1 * scale;
-                weights[index(c2)] += 1 * scale
-            },
-            [(cell, None), _, _] if cell.is_corner() => weights[index(cell)] += 1 * scale,
-            [_, _, (cell, None)] if cell.is_corner() => weights[index(cell)] += 1 * scale,
-            _ => {}
+impl GameSettings<'_> {
+    fn computer_options(&self) -> ComputerOptions {
+        ComputerOptions {
+            tie_break: *self.tie_break,
+            center_bonus: *self.center_bonus,
+            first_move: *self.first_move,
+            play_style: *self.play_style,
+            threat_priority: *self.threat_priority,
         }
-    });
-
-    info!("cell weights (higher is better): {:?}", weights);
-
-    let (index, _) = weights.iter().enumerate()
-        .filter(|(index, _)| game.get(Cell::variants()[*index]).is_none())
-        .max_by(|(_, &w1), (_, w2)| w1.cmp(w2)).expect("unable to find max weight");
-
-    let chosen_cell = Cell::variants()[index];
+    }
+}
 
-    info!("optimal cell for computer to choose is {:?} (on {} mode)", chosen_cell, difficulty);
+// where (and whether) the human is clicking or tapping
+#[derive(SystemParam)]
+struct PointerInput<'w, 's> {
+    windows: Query<'w, 's, &'static Window>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+    touch_input: Res<'w, Touches>,
+    mouse_button_input: Res<'w, ButtonInput<MouseButton>>,
+    focus: Res<'w, WindowFocus>,
+}
 
-    chosen_cell
+// the game being played, whose turn it is, and whose turn it will be
+#[derive(SystemParam)]
+struct Turn<'w> {
+    info: ResMut<'w, StateInfo>,
+    current_game_state: Res<'w, State<GameState>>,
+    next_game_state: ResMut<'w, NextState<GameState>>,
 }
 
 fn capture_input(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    windows: Query<&Window>,
-    cameras: Query<(&Camera, &GlobalTransform)>,
-    mut info: ResMut<StateInfo>,
-    cells: Query<(Entity, &Cell)>,
-    touch_input: Res<Touches>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
-    current_game_state: Res<State<GameState>>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-    game_mode: Res<GameMode>,
-    human_mark: Res<HumanMark>,
-    difficulty: Res<Difficulty>,
+    pointer: PointerInput,
+    board: BoardView,
+    turn: Turn,
+    settings: GameSettings,
+    mut rng: ResMut<GameRng>,
     time: Res<Time>,
 ) {
+    let PointerInput { windows, cameras, touch_input, mouse_button_input, focus } = pointer;
+    let BoardView { asset_server, scale, cells, previews, .. } = board;
+    let Turn { mut info, current_game_state, mut next_game_state } = turn;
+
+    // capture_input() only runs during XTurn and OTurn, but just in case it's ever scheduled at any other time...
+    if !matches!(current_game_state.get(), GameState::XTurn | GameState::OTurn) {
+        warn!("ignoring input, as capture_input() was called in the {:?} state", current_game_state.get());
+        return;
+    }
+
+    // a held mouse button should not place more than one mark, even across a change of turn
+    if info.awaiting_release && !mouse_button_input.pressed(MouseButton::Left) {
+        info.awaiting_release = false;
+    }
 
-    // if the winner has already been decided, we should ignore user input until a new game is started
-    if info.game.over() { return; }
+    // if the winner has already been decided (e.g. in a loaded position), there's nothing left to play
+    if info.game.over() {
+        next_game_state.set(GameState::GameOver);
+        return;
+    }
 
     // either "X" or "O"
     let mark = info.current_player;
 
-    let maybe_cell = match *game_mode {
-        GameMode::OnePlayer if !mark.is(*human_mark) => {
-            info.computer_thinking_time.tick(time.delta());
+    let maybe_cell = match *settings.game_mode {
+        GameMode::OnePlayer if !mark.is(*settings.human_mark) => {
+            // the human can't play while the computer is thinking
+            if mouse_button_input.just_pressed(MouseButton::Left) || touch_input.any_just_pressed() {
+                info.shake = true;
+            }
 
-            if info.computer_thinking_time.finished() {
-                Some(generate_computer_input(&info.game, mark, *difficulty))
-            } else {
+            // the computer doesn't "think" while nobody is watching
+            if focus.0 {
+                info.computer_thinking_time.tick(time.delta());
+            }
+
+            if !info.computer_thinking_time.finished() {
                 None
+            } else {
+                let game_seed = *info.game_seed.get_or_insert_with(|| rng.next_u64());
+
+                // when practising, the computer sticks to the recorded game for as long as the human does
+                let recorded = settings.practice.0.as_deref().and_then(|moves| recorded_move(&info.game, moves));
+
+                let cell = recorded.or_else(|| {
+                    let cell = generate_computer_input(&info.game, mark, *settings.difficulty, settings.computer_options(), game_seed, &mut rng);
+
+                    let forgiving = *settings.forgiveness == Forgiveness::Forgiving && settings.stats.losing_streak() >= FORGIVE_AFTER;
+                    if forgiving { cell.map(|cell| forgive(&info.game, mark, cell, &mut rng)) } else { cell }
+                });
+
+                match cell {
+                    Some(cell) => {
+                        if *settings.move_announcement == MoveAnnouncement::AnnounceMoves {
+                            info.announcement = Some((mark, cell));
+                        }
+
+                        // moves from the opening book weren't weighed up, so there are no alternatives to show
+                        if debug_mode() {
+                            let ranked = rank_cells(&info.game, mark, *settings.difficulty, settings.computer_options(), game_seed);
+
+                            if ranked.first().is_some_and(|(best, _)| *best == cell) {
+                                info.candidates = ranked;
+                            }
+                        }
+                        Some(cell)
+                    },
+                    None => {
+                        // this shouldn't happen, as the game is over as soon as the board is full
+                        warn!("the computer has no legal move, so ending the game");
+                        next_game_state.set(GameState::GameOver);
+                        return;
+                    }
+                }
             }
         },
         _ => {
-            let user_input = capture_user_input(windows, cameras, touch_input, mouse_button_input);
-            info.computer_thinking_time.set_duration(Duration::from_millis(400)); // feels about right?
+            let user_input = if info.awaiting_release {
+                None
+            } else {
+                let place_on = *settings.place_on;
+                capture_user_input(windows, cameras, touch_input, mouse_button_input, &scale, place_on, &mut info.pressed_cell)
+            };
+            info.computer_thinking_time.set_duration(settings.game_speed.scale(Duration::from_millis(400))); // feels about right?
             info.computer_thinking_time.reset();
-            user_input
+
+            match user_input {
+                // with two-tap placement, the first tap on an empty cell only previews the mark there
+                Some(UserInput::Touch(cell))
+                    if *settings.tap_to_confirm == TapToConfirm::TwoTaps
+                    && info.game.get(cell).is_none()
+                    && info.pending_cell != Some(cell) => {
+
+                    info.pending_cell = Some(cell);
+
+                    for entity in &previews {
+                        commands.entity(entity).despawn_recursive();
+                    }
+
+                    let (entity, _) = cells.iter().find(|(_, c)| **c == cell).expect("could not find tapped cell in all cells");
+
+                    commands.entity(entity).with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                mark.to_string(),
+                                TextStyle {
+                                    font_size: mark_font_size(scale.0),
+                                    font: asset_server.load("fonts/larabie.otf"),
+                                    color: mark.color().with_a(0.3),
+                                }
+                            ),
+                            Preview
+                        ));
+                    });
+
+                    None
+                }
+                Some(UserInput::Touch(cell)) | Some(UserInput::Click(cell)) => Some(cell),
+                None => None
+            }
         }
     };
 
@@ -631,54 +2484,198 @@ fn capture_input(
 
     // If the user / the computer did click on a cell...
     match info.game.get(cell) {
-        Some(_) => warn!("this cell is already occupied"),
+        Some(_) => match *settings.occupied_cell {
+            OccupiedCell::WarnOccupied => {
+                warn!("this cell is already occupied");
+                info.shake = true;
+
+                // the placement click, slowed right down, so it sounds more like a dull thud
+                if *settings.sound == Sound::SoundOn {
+                    commands.spawn(AudioBundle {
+                        source: asset_server.load("sounds/click.wav"),
+                        settings: PlaybackSettings { speed: 0.5, ..PlaybackSettings::DESPAWN },
+                    });
+                }
+            }
+            OccupiedCell::LogOccupied => warn!("this cell is already occupied"),
+            OccupiedCell::SilentOccupied => {}
+        },
+        // the human's opening move has to follow the FirstMove house rule (the computer's always does)
+        None if info.game.history().is_empty() && !settings.first_move.allows(cell) => {
+            info!("the first move can't be made in {:?}", cell);
+            info.shake = true;
+            info.lesson = Some(settings.first_move.rule().to_string());
+        }
         None => {
             // ...get a handle to the cell clicked
-            let (entity, cell) = cells.iter().filter(|(_, c)| c == &&cell).next().expect("could not find clicked cell in all cells");
+            let (entity, cell) = cells.iter().find(|(_, c)| c == &&cell).expect("could not find clicked cell in all cells");
+
+            // ...explain the human's move, before it's made, so it can be compared to the other moves they could have made
+            if *settings.teaching == Teaching::Explain && *settings.game_mode == GameMode::OnePlayer && mark.is(*settings.human_mark) {
+                info.lesson = explain_move(&info.game, *cell, mark);
+            }
 
             // ...and mark the cell as clicked by that player
             info.game.set(*cell, mark);
+            info.awaiting_release = true;
+
+            // any previewed mark is either being placed now, or is no longer wanted
+            info.pending_cell = None;
+            for entity in &previews {
+                commands.entity(entity).despawn_recursive();
+            }
             info!("{:?} was hit", cell);
 
             // draw the mark on the board
             commands.entity(entity).with_children(|parent| {
-                parent.spawn((
-                    TextBundle::from_section(
-                        mark.to_string(),
-                        TextStyle {
-                            font_size: 200.0,
-                            font: asset_server.load("fonts/larabie.otf"),
-                            color: mark.color(),
-                            ..default()
-                        }
-                    ),
-                    mark // tag the entity with the Mark Component
-                ));
+                if *settings.ripple == Ripple::RippleOn {
+                    spawn_ripple(parent, mark, *settings.game_speed);
+                }
+
+                spawn_mark(parent, mark, asset_server.load("fonts/larabie.otf"), scale.0);
+
+                // optionally, draw the move number
+                if *settings.move_numbers == MoveNumbers::Shown {
+                    spawn_move_number(parent, info.game.history().len(), mark, asset_server.load("fonts/larabie.otf"), scale.0);
+                }
             });
 
+            if *settings.sound == Sound::SoundOn {
+                // a subtle change in pitch, so that a whole game of clicks doesn't sound quite so mechanical
+                let speed = match *settings.placement_pitch {
+                    PlacementPitch::SamePitch => 1.0,
+                    PlacementPitch::VariedPitch => rng.gen_range(0.92..1.08),
+                };
+
+                commands.spawn(AudioBundle {
+                    source: asset_server.load("sounds/click.wav"),
+                    settings: PlaybackSettings { speed, ..PlaybackSettings::DESPAWN },
+                });
+            }
+
+            // If this is a puzzle, the game ends after this single move, so check whether it was the right one...
+            if let Some(goal) = info.puzzle {
+                let score = info.game.minimax(mark);
+
+                let solved = match goal {
+                    Goal::Win => score == 1,
+                    Goal::Draw => score >= 0,
+                };
+
+                info!("{:?} was {} for this puzzle", cell, if solved { "correct" } else { "incorrect" });
+
+                info.puzzle_solved = Some(solved);
+                next_game_state.set(GameState::GameOver)
+
             // If the game is over...
-            if info.game.over() {
+            } else if info.game.over() {
                 match info.game.winner() {
+                    None if *settings.overtime == Overtime::SuddenDeath => {
+                        info!("The game ends in a tie, so it goes to overtime");
+                        next_game_state.set(GameState::Overtime)
+                    }
                     None => {
                         info!("The game ends in a tie");
+                        next_game_state.set(GameState::GameOver)
                     }
                     Some((mark, line)) => {
                         let [from, .., to] = line.cells();
                         info!("The winner is {} along the line {:?} -> {:?}", mark, from, to);
+
+                        match *settings.game_speed {
+                            GameSpeed::Fast => next_game_state.set(GameState::GameOver),
+                            _ => next_game_state.set(GameState::InstantReplay),
+                        }
                     }
                 }
 
-                next_game_state.set(GameState::GameOver)
-
             } else {
                 // If the game is not over... keep playing
                 match *current_game_state.get() {
                     GameState::XTurn => next_game_state.set(GameState::OTurn),
                     GameState::OTurn => next_game_state.set(GameState::XTurn),
+                    GameState::Overtime => unreachable!("called capture_input() in Overtime state"),
+                    GameState::InstantReplay => unreachable!("called capture_input() in InstantReplay state"),
                     GameState::GameOver => unreachable!("called capture_input() in GameOver state"),
                     GameState::GameNotInProgress => unreachable!("called capture_input() in GameNotInProgress state"),
                 }
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_legal_game_state_transitions_are_allowed() {
+        use GameState::*;
+
+        let states = [GameNotInProgress, XTurn, OTurn, Overtime, InstantReplay, GameOver];
+
+        let legal = [
+            (GameNotInProgress, XTurn),
+            (GameNotInProgress, OTurn),
+            (XTurn, XTurn),
+            (XTurn, OTurn),
+            (XTurn, Overtime),
+            (XTurn, InstantReplay),
+            (XTurn, GameOver),
+            (OTurn, XTurn),
+            (OTurn, OTurn),
+            (OTurn, Overtime),
+            (OTurn, InstantReplay),
+            (OTurn, GameOver),
+            (Overtime, XTurn),
+            (InstantReplay, GameOver),
+            (GameOver, XTurn),
+            (GameOver, GameNotInProgress),
+        ];
+
+        for before in &states {
+            for after in &states {
+                let expected = legal.contains(&(before.clone(), after.clone()));
+                assert_eq!(before.can_become(after), expected, "{:?} -> {:?}", before, after);
+            }
+        }
+
+        assert!(!GameNotInProgress.can_become(&GameOver));
+    }
+
+    #[test]
+    fn undo_takes_back_the_computer_reply_too() {
+        // two players take back one move each
+        assert_eq!(plies_to_undo(GameMode::TwoPlayers, Mark::X, HumanMark::HumanX), 1);
+        assert_eq!(plies_to_undo(GameMode::TwoPlayers, Mark::O, HumanMark::HumanX), 1);
+
+        // against the computer, its reply goes too, unless it's still thinking about it
+        assert_eq!(plies_to_undo(GameMode::OnePlayer, Mark::X, HumanMark::HumanX), 2);
+        assert_eq!(plies_to_undo(GameMode::OnePlayer, Mark::O, HumanMark::HumanO), 2);
+        assert_eq!(plies_to_undo(GameMode::OnePlayer, Mark::O, HumanMark::HumanX), 1);
+    }
+
+    #[test]
+    fn each_game_starts_as_the_menu_chose() {
+        let mut rng = GameRng::seeded(0);
+        let mut menu = MenuHumanMark::default();
+        let mut human_mark = HumanMark::HumanO;
+
+        // sudden death overtime swaps the human's mark during a game, but the next game is back to the menu's choice
+        resolve_human_mark(&mut human_mark, &mut menu, &mut rng);
+        human_mark = HumanMark::HumanX;
+        resolve_human_mark(&mut human_mark, &mut menu, &mut rng);
+        assert!(human_mark == HumanMark::HumanO);
+
+        // with HumanRandom, every game is X or O, and HumanRandom is still chosen on the menu afterwards
+        let mut menu = MenuHumanMark::default();
+        let mut human_mark = HumanMark::HumanRandom;
+
+        for _ in 0..10 {
+            resolve_human_mark(&mut human_mark, &mut menu, &mut rng);
+            assert!(human_mark != HumanMark::HumanRandom);
+        }
+
+        assert!(menu.0 == Some(HumanMark::HumanRandom));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,127 @@
+use crate::board::Mark;
+
+// an N x N board, where a player must fill an entire row, column, or diagonal (N in a row) to win
+//
+// board::Game is the classic 3x3 board, which the rest of the app (puzzles, notation, the opening book, ...) is built
+// around, so this is only used for the larger boards, like 4x4 "four-in-a-row"
+//
+// cells are numbered 0 to N*N - 1, left-to-right, top-to-bottom
+#[derive(Clone)]
+pub struct Grid {
+    size: usize,
+    marks: Vec<Option<Mark>>,
+    history: Vec<usize>,
+    winner: Option<(Mark, Vec<usize>)>,
+    over: bool
+}
+
+// like board::Game, two Grids are equal if their boards are, however the marks got there
+impl PartialEq for Grid {
+    fn eq(&self, other: &Grid) -> bool {
+        self.size == other.size && self.marks == other.marks
+    }
+}
+
+impl Eq for Grid {}
+
+impl Grid {
+    pub fn new(size: usize) -> Grid {
+        Grid { size, marks: vec![None; size * size], history: vec![], winner: None, over: false }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn row(&self, cell: usize) -> usize {
+        cell / self.size
+    }
+
+    pub fn column(&self, cell: usize) -> usize {
+        cell % self.size
+    }
+
+    // every row, every column, and the two long diagonals, as lists of cells
+    pub fn lines(&self) -> Vec<Vec<usize>> {
+        let n = self.size;
+
+        let rows = (0..n).map(|row| (0..n).map(|column| row * n + column).collect());
+        let columns = (0..n).map(|column| (0..n).map(|row| row * n + column).collect());
+        let down = (0..n).map(|i| i * n + i).collect();
+        let up = (0..n).map(|i| (n - 1 - i) * n + i).collect();
+
+        rows.chain(columns).chain([down, up]).collect()
+    }
+
+    // whose turn it is on this board, given that X always moves first
+    pub fn next_player(&self) -> Mark {
+        match self.history.len() % 2 {
+            0 => Mark::X,
+            _ => Mark::O,
+        }
+    }
+
+    pub fn empty_cells(&self) -> Vec<usize> {
+        (0..self.marks.len()).filter(|cell| self.marks[*cell].is_none()).collect()
+    }
+
+    fn determine_winner(&self) -> Option<(Mark, Vec<usize>)> {
+        self.lines().into_iter().find_map(|line| {
+            let first = self.marks[line[0]]?;
+            line.iter().all(|cell| self.marks[*cell] == Some(first)).then_some((first, line))
+        })
+    }
+
+    // behind a getter so the user cannot mutate this field directly
+    // the winning line is returned as a list of cells
+    pub fn winner(&self) -> Option<(Mark, Vec<usize>)> {
+        self.winner.clone()
+    }
+
+    // behind a getter so the user cannot mutate this field directly
+    pub fn over(&self) -> bool {
+        self.over
+    }
+
+    // behind a getter so the user cannot mutate the move history directly
+    pub fn history(&self) -> &[usize] {
+        &self.history
+    }
+
+    // behind a getter so the user cannot access / mutate marks directly
+    pub fn get(&self, cell: usize) -> Option<Mark> {
+        self.marks[cell]
+    }
+
+    // a copy of this grid with one more move made, leaving this grid as it is (e.g. to look ahead)
+    pub fn with_move(&self, cell: usize, mark: Mark) -> Grid {
+        let mut next = self.clone();
+        next.set(cell, mark);
+        next
+    }
+
+    // behind a setter so we can recalculate the winner immediately
+    // callers check that the cell is empty first, so marking an occupied cell is a bug (caught in debug builds)
+    pub fn set(&mut self, cell: usize, mark: Mark) {
+        debug_assert!(self.get(cell).is_none(), "cell {} is already marked", cell);
+        self.marks[cell] = Some(mark);
+        self.history.push(cell);
+        self.winner = self.determine_winner();
+        self.over = self.winner.is_some() || self.marks.iter().all(Option::is_some);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids_reaching_the_same_position_are_equal() {
+        let one = Grid::new(4).with_move(0, Mark::X).with_move(5, Mark::O).with_move(15, Mark::X);
+        let other = Grid::new(4).with_move(15, Mark::X).with_move(5, Mark::O).with_move(0, Mark::X);
+
+        assert!(one.history() != other.history());
+        assert!(one == other);
+        assert!(one != Grid::new(4).with_move(0, Mark::X).with_move(5, Mark::O));
+        assert!(Grid::new(3) != Grid::new(4));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,291 @@
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::{RequestRedraw, WindowResized};
+use rand::RngCore;
+use tic_tac_toe::ai::{generate_grid_input, search_depth};
+use tic_tac_toe::grid::Grid;
+use tic_tac_toe::settings::{BoardOutline, BoardSize, GameMode, GameSpeed, GridColor, HumanMark, PowerSaving, Theme, TieColor, WinningLine};
+use tic_tac_toe::theme_file::ThemeFiles;
+
+use crate::{AppState, clear_entities, clear_entities_with_state, draw_screen, spawn_modal};
+use crate::game::{HumanSide, Matchup, restore_human_mark};
+use crate::help::help_hidden;
+
+// the larger boards (see BoardSize), which are played on their own screen, as the main game screen (game.rs) and
+// everything built on it (puzzles, notation, instant replay, ...) only knows about the classic 3x3 board
+//
+// cells here are UI nodes with an Interaction, so Bevy works out which cell was clicked, however big the board is
+pub fn plugin(app: &mut App) {
+    app
+        .insert_resource(GridGame::default())
+        .add_systems(OnEnter(AppState::GridGame), start_grid_game)
+        .add_systems(Update, play_grid_game.run_if(in_state(AppState::GridGame)).run_if(help_hidden))
+        .add_systems(Update, grid_game_buttons.run_if(in_state(AppState::GridGame)).run_if(help_hidden))
+        .add_systems(Update, resize_grid_marks.run_if(in_state(AppState::GridGame)))
+        .add_systems(Last, keep_awake.run_if(in_state(AppState::GridGame)))
+        .add_systems(OnExit(AppState::GridGame), (clear_entities_with_state(AppState::GridGame), clear_entities::<GridGameOverlay>, remove_theme, restore_human_mark));
+}
+
+#[derive(Resource)]
+struct GridGame {
+    grid: Grid,
+    computer_thinking_time: Timer,
+    game_seed: u64,
+}
+
+impl Default for GridGame {
+    fn default() -> Self {
+        GridGame { grid: Grid::new(4), computer_thinking_time: Timer::default(), game_seed: 0 }
+    }
+}
+
+// tags each cell of the board with its index in the Grid
+#[derive(Component)]
+struct GridCell(usize);
+
+// tags the "X" and "O" text drawn in cells, so it can be resized with the window
+#[derive(Component)]
+struct GridMark;
+
+#[derive(Component)]
+enum GridGameButton {
+    PlayAgain,
+    BackToMenu,
+}
+
+#[derive(Component)]
+struct GridGameOverlay;
+
+// the board fills the same share of the window as the 3x3 board does on the game screen (750px of 800px)
+const BOARD_FILL: f32 = 93.75;
+
+// marks fill most of their cell, like on the game screen
+// font sizes are multiplied by the UiScale when drawn, so that's divided back out of the window's size
+fn mark_font_size(windows: &Query<&Window>, ui_scale: &UiScale, size: usize) -> f32 {
+    let window = windows.single();
+    let board = window.width().min(window.height()) * BOARD_FILL / 100.0 / ui_scale.0;
+    0.8 * board / size as f32
+}
+
+// everything needed to draw the board, the marks on it, and the result at the end
+#[derive(SystemParam)]
+struct GridView<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    windows: Query<'w, 's, &'static Window>,
+    ui_scale: Res<'w, UiScale>,
+    theme: Res<'w, Theme>,
+    theme_files: Res<'w, ThemeFiles>,
+    outline: Res<'w, BoardOutline>,
+    grid_color: Res<'w, GridColor>,
+    winning_line: Res<'w, WinningLine>,
+    tie_color: Res<'w, TieColor>,
+}
+
+fn start_grid_game(
+    mut commands: Commands,
+    mut game: ResMut<GridGame>,
+    mut side: HumanSide,
+    mut clear_color: ResMut<ClearColor>,
+    board_size: Res<BoardSize>,
+    view: GridView,
+) {
+    let GridView { theme, theme_files, outline, grid_color, .. } = view;
+    let size = board_size.cells();
+
+    side.resolve();
+
+    *game = GridGame { grid: Grid::new(size), game_seed: side.rng.next_u64(), ..default() };
+
+    clear_color.0 = theme_files.background(*theme);
+
+    draw_screen(&mut commands, AppState::GridGame).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: Style {
+                display: Display::Grid,
+                grid_template_rows: vec![GridTrack::flex(1.0); size],
+                grid_template_columns: vec![GridTrack::flex(1.0); size],
+                width: Val::VMin(BOARD_FILL),
+                height: Val::VMin(BOARD_FILL),
+                ..default()
+            },
+            ..default()
+        }).with_children(|parent| {
+            const NONE: Val = Val::ZERO;
+            const THIN: Val = Val::Px(3.0);
+
+            // each grid line is drawn half by the cell on either side of it, as is the outline, with BoardOutline::Outlined
+            let outer = if *outline == BoardOutline::Outlined { Val::Px(6.0) } else { NONE };
+
+            for cell in 0..size * size {
+                let (row, column) = (game.grid.row(cell), game.grid.column(cell));
+
+                let border = UiRect::new(
+                    if column > 0 { THIN } else { outer },
+                    if column < size - 1 { THIN } else { outer },
+                    if row > 0 { THIN } else { outer },
+                    if row < size - 1 { THIN } else { outer },
+                );
+
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            display: Display::Grid,
+                            justify_items: JustifyItems::Center,
+                            align_items: AlignItems::Center,
+                            border,
+                            ..default()
+                        },
+                        border_color: theme_files.grid(*theme, *grid_color).into(),
+                        ..default()
+                    },
+                    Interaction::default(),
+                    GridCell(cell)
+                ));
+            }
+        });
+    });
+}
+
+fn remove_theme(mut clear_color: ResMut<ClearColor>) {
+    clear_color.0 = Theme::Classic.background();
+}
+
+fn play_grid_game(
+    mut commands: Commands,
+    mut game: ResMut<GridGame>,
+    mut cells: Query<(Entity, &GridCell, &Interaction, &mut BackgroundColor)>,
+    view: GridView,
+    matchup: Matchup,
+    game_speed: Res<GameSpeed>,
+    time: Res<Time>,
+) {
+    let GridView { asset_server, windows, ui_scale, theme_files, winning_line, tie_color, .. } = view;
+    let Matchup { game_mode, human_mark, difficulty } = matchup;
+
+    if game.grid.over() { return; }
+
+    let mark = game.grid.next_player();
+    let font = asset_server.load("fonts/larabie.otf");
+
+    let chosen = if *game_mode == GameMode::OnePlayer && !mark.is(*human_mark) {
+        game.computer_thinking_time.tick(time.delta());
+
+        if !game.computer_thinking_time.finished() { return; }
+
+        generate_grid_input(&game.grid, mark, *difficulty, game.game_seed, search_depth(game.grid.size()))
+    } else {
+        game.computer_thinking_time = Timer::new(game_speed.scale(Duration::from_millis(400)), TimerMode::Once);
+
+        cells.iter()
+            .find(|(_, cell, interaction, _)| **interaction == Interaction::Pressed && game.grid.get(cell.0).is_none())
+            .map(|(_, cell, _, _)| cell.0)
+    };
+
+    let Some(chosen) = chosen else { return; };
+
+    game.grid.set(chosen, mark);
+    info!("cell {} was hit", chosen);
+
+    let font_size = mark_font_size(&windows, &ui_scale, game.grid.size());
+
+    for (entity, cell, _, _) in cells.iter() {
+        if cell.0 == chosen {
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(mark.to_string(), TextStyle { font: font.clone(), font_size, color: theme_files.mark(mark) }),
+                    GridMark
+                ));
+            });
+        }
+    }
+
+    if !game.grid.over() { return; }
+
+    let title = match game.grid.winner() {
+        None => vec![("It's a tie!".to_string(), tie_color.color())],
+        Some((winner, line)) => {
+            if *winning_line == WinningLine::Highlighted {
+                for (_, cell, _, mut background) in cells.iter_mut() {
+                    if line.contains(&cell.0) {
+                        *background = winner.color().with_a(0.2).into();
+                    }
+                }
+            }
+
+            vec![(winner.to_string(), winner.color()), (" wins!".to_string(), Color::BLACK)]
+        }
+    };
+
+    spawn_modal(&mut commands, font, title, vec![], vec![
+        ("play again", Color::BLUE, GridGameButton::PlayAgain),
+        ("back to menu", Color::RED, GridGameButton::BackToMenu),
+    ]).insert(GridGameOverlay);
+}
+
+// with PowerSaving::LowPower, keeps updating while the computer is thinking (see game::keep_awake)
+fn keep_awake(
+    power: Res<PowerSaving>,
+    game: Res<GridGame>,
+    game_mode: Res<GameMode>,
+    human_mark: Res<HumanMark>,
+    mut redraw: EventWriter<RequestRedraw>,
+) {
+    let computer_turn = *game_mode == GameMode::OnePlayer && !game.grid.over() && !game.grid.next_player().is(*human_mark);
+
+    if *power == PowerSaving::LowPower && computer_turn {
+        redraw.send(RequestRedraw);
+    }
+}
+
+// the marks, and the overlay over them, which are cleared away to play again
+type Leftovers = Or<(With<GridGameOverlay>, With<GridMark>)>;
+
+fn grid_game_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &GridGameButton), Changed<Interaction>>,
+    leftovers: Query<Entity, Leftovers>,
+    mut cells: Query<&mut BackgroundColor, With<GridCell>>,
+    mut game: ResMut<GridGame>,
+    mut side: HumanSide,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed { continue; }
+
+        match button {
+            GridGameButton::PlayAgain => {
+                for entity in &leftovers {
+                    commands.entity(entity).despawn_recursive();
+                }
+
+                for mut background in cells.iter_mut() {
+                    *background = Color::NONE.into();
+                }
+
+                side.resolve();
+                *game = GridGame { grid: Grid::new(game.grid.size()), game_seed: side.rng.next_u64(), ..default() };
+            }
+            GridGameButton::BackToMenu => next_app_state.set(AppState::Menu),
+        }
+    }
+}
+
+fn resize_grid_marks(
+    mut resized: EventReader<WindowResized>,
+    mut marks: Query<&mut Text, With<GridMark>>,
+    windows: Query<&Window>,
+    ui_scale: Res<UiScale>,
+    game: Res<GridGame>,
+) {
+    if resized.read().last().is_none() { return; }
+
+    let font_size = mark_font_size(&windows, &ui_scale, game.grid.size());
+
+    for mut text in marks.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.font_size = font_size;
+        }
+    }
+}
\ No newline at end of file
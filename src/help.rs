@@ -0,0 +1,120 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use tic_tac_toe::settings::TapToConfirm;
+
+use crate::{AppState, Dismissable, spawn_modal};
+use crate::menu::hover_button;
+
+pub fn plugin(app: &mut App) {
+    app
+        .add_systems(Update, hover_button::<HelpButton>)
+        // in PostUpdate, so that a click which closes the overlay is not also seen by the (paused) screen underneath
+        .add_systems(PostUpdate, toggle_help);
+}
+
+// the "help" button on the menu screen
+#[derive(Component)]
+pub struct OpenHelp;
+
+#[derive(Component)]
+struct HelpButton;
+
+#[derive(Component)]
+pub struct HelpOverlay;
+
+// run condition for any system which should be paused while the help overlay is shown
+pub fn help_hidden(overlays: Query<(), With<HelpOverlay>>) -> bool {
+    overlays.is_empty()
+}
+
+// the keys (and clicks) which do something on each screen, as (key, description) pairs
+fn shortcuts(state: AppState, tap_to_confirm: TapToConfirm) -> Vec<(&'static str, &'static str)> {
+    let mut shortcuts = match state {
+        AppState::Menu => vec![("click", "pick settings, then a mode"), ("up / down", "pick a setting"), ("left / right", "change it")],
+        AppState::Options => vec![("click", "change a setting"), ("scroll", "see more settings")],
+        AppState::Puzzles => vec![("click", "pick a puzzle")],
+        AppState::Stats => vec![],
+        AppState::Game => match tap_to_confirm {
+            TapToConfirm::OneTap => vec![("click / tap", "place a mark")],
+            TapToConfirm::TwoTaps => vec![("click", "place a mark"), ("tap twice", "place a mark")],
+        },
+        AppState::GridGame => vec![("click / tap", "place a mark")],
+        AppState::Editor => vec![("click", "change a cell"), ("one / two players", "play from here")],
+        AppState::Spectate => vec![("type", "the host's address"), ("enter", "start watching")],
+    };
+
+    if state == AppState::Game {
+        shortcuts.push(("ctrl + z", "take back a move"));
+        shortcuts.push(("ctrl + r", "resign"));
+        shortcuts.push(("ctrl + shift + c", "copy the moves so far"));
+        shortcuts.push(("enter / esc", "play again / menu, once it's over"));
+    }
+
+    if state == AppState::Game && cfg!(debug_assertions) {
+        shortcuts.push(("x o . enter", "type in a board (debug only)"));
+        shortcuts.push(("ctrl + c", "copy the board (debug only)"));
+        shortcuts.push(("f12", "save a picture (debug only)"));
+        shortcuts.push(("shift + f12", "...without the result (debug only)"));
+    }
+
+    shortcuts.push(("?", "show / hide this help"));
+    shortcuts.push(("esc", "close this help"));
+    shortcuts
+}
+
+// the buttons which open and close the help overlay
+#[derive(SystemParam)]
+struct HelpButtons<'w, 's> {
+    open: Query<'w, 's, &'static Interaction, (Changed<Interaction>, With<OpenHelp>)>,
+    close: Query<'w, 's, &'static Interaction, (Changed<Interaction>, With<HelpButton>)>,
+}
+
+fn toggle_help(
+    mut commands: Commands,
+    mut keys: EventReader<KeyboardInput>,
+    buttons: HelpButtons,
+    overlays: Query<Entity, With<HelpOverlay>>,
+    state: Res<State<AppState>>,
+    tap_to_confirm: Res<TapToConfirm>,
+    asset_server: Res<AssetServer>,
+) {
+    let mut question_mark = false;
+
+    // escape closes the overlay too, as it does every Dismissable modal (see dismiss_modal())
+    for event in keys.read().filter(|event| event.state == ButtonState::Pressed) {
+        if let Key::Character(c) = &event.logical_key {
+            if c == "?" { question_mark = true; }
+        }
+    }
+
+    let shown = !overlays.is_empty();
+
+    let close = shown && (question_mark || buttons.close.iter().any(|i| *i == Interaction::Pressed));
+    let open = !shown && (question_mark || buttons.open.iter().any(|i| *i == Interaction::Pressed));
+
+    if close {
+        for entity in &overlays {
+            commands.entity(entity).despawn_recursive();
+        }
+    } else if open {
+        let lines = shortcuts(*state.get(), *tap_to_confirm).into_iter()
+            .map(|(key, description)| format!("{}  -  {}", key, description))
+            .collect();
+
+        spawn_modal(
+            &mut commands,
+            asset_server.load("fonts/larabie.otf"),
+            vec![("help".to_string(), Color::BLACK)],
+            lines,
+            vec![("close", Color::BLUE, HelpButton)],
+        ).insert((
+            HelpOverlay,
+            Dismissable,
+            FocusPolicy::Block, // stop buttons underneath the overlay from being pressed
+            ZIndex::Global(2) // draw above the game over overlay, if there is one
+        ));
+    }
+}
\ No newline at end of file
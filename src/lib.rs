@@ -0,0 +1,24 @@
+// the rules of tic-tac-toe and the computer player, kept separate from the Bevy app in main.rs
+// so that other frontends (see src/bin/) can reuse them without running an App
+use macros::Enumerated;
+
+pub mod ai;
+pub mod board;
+pub mod broadcast;
+pub mod grid;
+pub mod recording;
+pub mod rng;
+pub mod settings;
+pub mod snapshot;
+pub mod stats;
+pub mod theme_file;
+
+pub trait Enumerated {
+    type Item;
+    const CARDINALITY: usize;
+    fn variants() -> Vec<Self::Item>;
+
+    // the variant after (or before) this one, in declaration order, wrapping around at either end
+    fn next(&self) -> Self::Item;
+    fn prev(&self) -> Self::Item;
+}
@@ -1,36 +1,98 @@
+use std::time::Duration;
+
 use bevy::asset::AssetMetaCheck;
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
-use macros::Enumerated;
+use bevy::winit::{UpdateMode, WinitSettings, WinitWindows};
+use tic_tac_toe::rng::GameRng;
+use tic_tac_toe::settings;
+use tic_tac_toe::stats::{Skill, Stats};
+use winit::window::Icon;
 
 mod menu;
 mod game;
-mod settings;
+mod options;
+mod puzzles;
+mod help;
+mod fonts;
+mod scores;
+mod grid_game;
+mod editor;
+mod spectate;
 
 #[derive(States, Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 enum AppState {
     #[default]
     Menu,
+    Options,
+    Puzzles,
+    Stats,
     Game,
-}
-
-trait Enumerated {
-    type Item;
-    const CARDINALITY: usize;
-    fn variants() -> Vec<Self::Item>;
+    GridGame,
+    Editor,
+    Spectate,
 }
 
 fn main() {
+    let stats = Stats::load(scores::STATS_FILE);
+
     App::new()
         .insert_resource(AssetMetaCheck::Never) // https://github.com/bevyengine/bevy/issues/10157#issuecomment-1849092112
         .insert_resource(settings::GameMode::default())
         .insert_resource(settings::HumanMark::default())
         .insert_resource(settings::Difficulty::default())
+        .insert_resource(settings::MoveNumbers::default())
+        .insert_resource(settings::MatchLength::default())
+        .insert_resource(settings::SideSwap::default())
+        .insert_resource(settings::GameSpeed::default())
+        .insert_resource(settings::Sound::default())
+        .insert_resource(settings::TapToConfirm::default())
+        .insert_resource(settings::WinningLine::default())
+        .insert_resource(settings::Ripple::default())
+        .insert_resource(settings::LineHints::default())
+        .insert_resource(settings::AutoRestart::default())
+        .insert_resource(settings::Overtime::default())
+        .insert_resource(settings::Theme::default())
+        .insert_resource(settings::BoardSize::default())
+        .insert_resource(settings::PlaceOn::default())
+        .insert_resource(settings::BoardOutline::default())
+        .insert_resource(settings::Personality::default())
+        .insert_resource(settings::ResultEmoji::default())
+        .insert_resource(settings::BoardShake::default())
+        .insert_resource(settings::Forgiveness::default())
+        .insert_resource(settings::ResultOverlay::default())
+        .insert_resource(settings::TieBreak::default())
+        .insert_resource(settings::Countdown::default())
+        .insert_resource(settings::Teaching::default())
+        .insert_resource(settings::CenterBonus::default())
+        .insert_resource(settings::MarkShading::default())
+        .insert_resource(settings::FirstMove::default())
+        .insert_resource(settings::PowerSaving::default())
+        .insert_resource(settings::PlacementPitch::default())
+        .insert_resource(settings::CellsLeft::default())
+        .insert_resource(settings::PlayStyle::default())
+        .insert_resource(settings::TurnPrompt::default())
+        .insert_resource(settings::OccupiedCell::default())
+        .insert_resource(settings::ThreatPriority::default())
+        .insert_resource(settings::MoveAnnouncement::default())
+        .insert_resource(settings::ResumeCue::default())
+        .insert_resource(settings::TieColor::default())
+        .insert_resource(settings::AutoDifficulty::default())
+        .insert_resource(settings::load_setting::<settings::GridColor>(options::GRID_COLOR_FILE))
+        .insert_resource(settings::load_setting::<settings::UiSize>(options::UI_SIZE_FILE))
+        .insert_resource(settings::Hosting::default())
+        .insert_resource(GameRng::default())
+        .insert_resource(Skill::from_stats(&stats))
+        .insert_resource(stats)
         .add_plugins(DefaultPlugins)
-        .insert_resource(ClearColor(Color::rgb(0.9, 0.9, 0.9)))
+        .insert_resource(ClearColor(settings::Theme::Classic.background()))
         .init_state::<AppState>()
-        .add_systems(Startup, setup)
-        .add_plugins((menu::plugin, game::plugin))
+        .add_systems(Startup, (setup, set_window_icon))
+        .insert_resource(OpenModals::default())
+        .add_systems(Update, (apply_ui_size, apply_power_saving))
+        .add_systems(PreUpdate, track_modals)
+        .add_systems(PostUpdate, dismiss_modal)
+        .add_plugins((menu::plugin, options::plugin, puzzles::plugin, game::plugin, help::plugin, fonts::plugin, scores::plugin, grid_game::plugin, editor::plugin, spectate::plugin))
         .run();
 }
 
@@ -40,10 +102,58 @@ fn setup(
 ) {
     let mut window = windows.single_mut();
     window.resolution.set(800.0, 800.0);
-    window.resizable = false;
+    window.resizable = true;
+    window.title = "Tic Tac Toe".to_string();
     commands.spawn(Camera2dBundle::default());
 }
 
+// Bevy has no API for window icons (yet), so we have to go through winit directly
+// https://bevy-cheatbook.github.io/window/icon.html
+// this is a no-op on the web, where the favicon in index.html is used instead
+fn set_window_icon(windows: NonSend<WinitWindows>) {
+    let image = image::load_from_memory(include_bytes!("../assets/icon.png"))
+        .expect("icon.png should be a valid image")
+        .into_rgba8();
+
+    let (width, height) = image.dimensions();
+    let icon = Icon::from_rgba(image.into_raw(), width, height).expect("icon.png should be a valid icon");
+
+    for window in windows.windows.values() {
+        window.set_window_icon(Some(icon.clone()));
+    }
+}
+
+// everything on screen is scaled up by the UiSize, so the window is scaled up along with it, so that every screen still fits
+// the choice is saved whenever it changes (but not when it's first loaded)
+fn apply_ui_size(size: Res<settings::UiSize>, mut ui_scale: ResMut<UiScale>, mut windows: Query<&mut Window>) {
+    if !size.is_changed() { return; }
+
+    ui_scale.0 = size.factor();
+
+    for mut window in windows.iter_mut() {
+        window.resolution.set(800.0 * size.factor(), 800.0 * size.factor());
+    }
+
+    if !size.is_added() {
+        if let Err(error) = settings::save_setting(*size, options::UI_SIZE_FILE) {
+            warn!("could not save UI size to {}: {}", options::UI_SIZE_FILE, error);
+        }
+    }
+}
+
+// with PowerSaving::LowPower, the app sleeps until there's some input (a click, a key, the cursor moving over the window)
+// anything which moves on screen, or counts down a timer, has to ask for the next update itself (see game::keep_awake)
+//
+// it still wakes up a few times a second, e.g. so text appears once its font has finished loading
+fn apply_power_saving(power: Res<settings::PowerSaving>, mut winit: ResMut<WinitSettings>) {
+    if !power.is_changed() { return; }
+
+    winit.focused_mode = match *power {
+        settings::PowerSaving::FullSpeed => UpdateMode::Continuous,
+        settings::PowerSaving::LowPower => UpdateMode::ReactiveLowPower { wait: Duration::from_millis(250) },
+    };
+}
+
 fn draw_screen<'a>(commands: &'a mut Commands, state: AppState) -> EntityCommands<'a> {
     commands
         .spawn((
@@ -61,8 +171,165 @@ fn draw_screen<'a>(commands: &'a mut Commands, state: AppState) -> EntityCommand
         ))
 }
 
+// draws a full-screen overlay with a centered inner window, holding a title above a column of buttons
+//
+// the title is a list of (text, color) sections, so that e.g. "X wins!" can color the "X" differently
+// any lines of smaller text (e.g. help) are drawn between the title and the buttons
+// each button is a (label, color, marker) triple, where the marker Component identifies the button when pressed
+fn spawn_modal<'a, T: Component>(
+    commands: &'a mut Commands,
+    font: Handle<Font>,
+    title: Vec<(String, Color)>,
+    lines: Vec<String>,
+    buttons: Vec<(&str, Color, T)>,
+) -> EntityCommands<'a> {
+
+    // entire screen
+    let mut modal = commands.spawn(
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                left: Val::ZERO,
+                top: Val::ZERO,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+            z_index: ZIndex::Global(1),
+            ..default()
+        }
+    );
+
+    modal.with_children(|parent| {
+
+        // inner window
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(61.8),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::rgba(1.0, 1.0, 1.0, 0.85).into(),
+                ..default()
+            },
+            ModalWindow
+        )).with_children(|parent| {
+
+            // top row
+            parent.spawn(NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            }).with_children(|parent| {
+                for (text, color) in title {
+                    parent.spawn(TextBundle::from_section(
+                        text,
+                        TextStyle {
+                            color,
+                            font_size: 75.0,
+                            font: font.clone(),
+                        }
+                    ));
+                }
+            });
+
+            for line in lines {
+                parent.spawn(
+                    TextBundle::from_section(
+                        line,
+                        TextStyle {
+                            color: Color::BLACK,
+                            font_size: 30.0,
+                            font: font.clone(),
+                        }
+                    ).with_style(Style {
+                        align_self: AlignSelf::Center,
+                        margin: UiRect::vertical(Val::Px(5.0)),
+                        ..default()
+                    })
+                );
+            }
+
+            for (text, color, marker) in buttons {
+                parent.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            justify_content: JustifyContent::Center,
+                            padding: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+                        ..default()
+                    },
+                    marker
+                )).with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        text,
+                        TextStyle {
+                            color,
+                            font_size: 60.0,
+                            font: font.clone(),
+                        }
+                    ));
+                });
+            }
+        });
+    });
+
+    modal
+}
+
+// tags the inner window of a modal, e.g. so its background can be changed after it's spawned
+#[derive(Component)]
+struct ModalWindow;
+
+// tags a modal which escape closes, when it's the one on top, just like its "close" or "cancel" button would
+#[derive(Component)]
+struct Dismissable;
+
+// every Dismissable modal on screen, oldest first, so that escape only ever closes the newest one
+#[derive(Resource, Default)]
+struct OpenModals(Vec<Entity>);
+
+// in PreUpdate, so that a modal opened by a press of escape (e.g. "back to menu", asking to confirm) isn't closed
+// again by that same press
+fn track_modals(modals: Query<Entity, With<Dismissable>>, added: Query<Entity, Added<Dismissable>>, mut open: ResMut<OpenModals>) {
+    open.0.retain(|entity| modals.contains(*entity));
+    open.0.extend(added.iter());
+}
+
+// escape is handled here for every modal, rather than by each one, so that they all behave the same way
+// this is in PostUpdate, like toggle_help(), because anything underneath a modal is paused until it's closed
+fn dismiss_modal(mut commands: Commands, keys: Res<ButtonInput<KeyCode>>, mut open: ResMut<OpenModals>) {
+    if !keys.just_pressed(KeyCode::Escape) { return; }
+
+    if let Some(modal) = open.0.pop().and_then(|entity| commands.get_entity(entity)) {
+        modal.despawn_recursive();
+    }
+}
+
 fn clear_entities<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
     for entity in &to_despawn {
         commands.entity(entity).despawn_recursive();
     }
 }
+
+// like clear_entities::<AppState>, but only for the screen of one state, so that the screen being moved to is left alone,
+// even if it's already been drawn by the time this runs
+fn clear_entities_with_state(state: AppState) -> impl Fn(Query<(Entity, &AppState)>, Commands) {
+    move |screens, mut commands| {
+        for (entity, screen) in &screens {
+            if *screen == state {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
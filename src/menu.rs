@@ -1,24 +1,191 @@
 use bevy::prelude::*;
+use bevy::utils::HashMap;
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::settings::{AutoDifficulty, BoardSize, Difficulty, GameMode, GameSpeed, HumanMark, Personality, Setting, Sound};
+use tic_tac_toe::recording::Recording;
+use tic_tac_toe::snapshot::GameSnapshot;
+use tic_tac_toe::stats::Skill;
 
-use crate::{AppState, clear_entities, draw_screen, Enumerated};
-use crate::settings::{Difficulty, GameMode, HumanMark, Setting};
+use crate::{AppState, clear_entities_with_state, draw_screen};
+use crate::game::{Practice, RECORDING_FILE, Resume, SNAPSHOT_FILE};
+use crate::help::OpenHelp;
 
 pub fn plugin(app: &mut App) {
     app
+        .insert_resource(OnePlayerSettingsLocked(false))
+        .insert_resource(FocusedRow(None))
         .add_systems(OnEnter(AppState::Menu), setup)
-        .add_systems(Update, update_setting::<HumanMark>.run_if(in_state(AppState::Menu)))
-        .add_systems(Update, hover_setting_button::<HumanMark>.run_if(in_state(AppState::Menu)))
-        .add_systems(Update, hover_setting_button::<Difficulty>.run_if(in_state(AppState::Menu)))
-        .add_systems(Update, hover_start_button.run_if(in_state(AppState::Menu)))
-        .add_systems(Update, update_setting::<Difficulty>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, update_setting::<HumanMark>.run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, hover_setting_button::<HumanMark>.run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, hover_setting_button::<Difficulty>.run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, update_setting::<Personality>.run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, hover_setting_button::<Personality>.run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, apply_personality)
+        .add_systems(Update, (lock_one_player_settings, dim_one_player_settings).chain().run_if(in_state(AppState::Menu)))
+        .add_systems(Update, update_setting::<BoardSize>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_setting_button::<BoardSize>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, move_focus.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, cycle_setting::<BoardSize, 0>.before(hover_setting_button::<BoardSize>).run_if(in_state(AppState::Menu)))
+        .add_systems(Update, cycle_setting::<Personality, 1>.before(hover_setting_button::<Personality>).run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, cycle_setting::<Difficulty, 2>.before(hover_setting_button::<Difficulty>).run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, cycle_setting::<HumanMark, 3>.before(hover_setting_button::<HumanMark>).run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, show_focus::<BoardSize, 0>.after(hover_setting_button::<BoardSize>).run_if(in_state(AppState::Menu)))
+        .add_systems(Update, show_focus::<Personality, 1>.after(hover_setting_button::<Personality>).run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, show_focus::<Difficulty, 2>.after(hover_setting_button::<Difficulty>).run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, show_focus::<HumanMark, 3>.after(hover_setting_button::<HumanMark>).run_if(in_state(AppState::Menu)).run_if(unlocked))
+        .add_systems(Update, hover_button::<GameMode>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<OpenOptions>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<OpenPuzzles>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<OpenHelp>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<OpenStats>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<OpenEditor>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<ContinueGame>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<PracticeGame>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, hover_button::<OpenSpectate>.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, update_setting::<Difficulty>.run_if(in_state(AppState::Menu)).run_if(unlocked))
         .add_systems(Update, start.run_if(in_state(AppState::Menu)))
-        .add_systems(OnExit(AppState::Menu), clear_entities::<AppState>);
+        .add_systems(Update, open_options.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, open_puzzles.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, open_stats.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, open_editor.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, continue_game.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, practice_game.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, open_spectate.run_if(in_state(AppState::Menu)))
+        .add_systems(Update, play_button_sounds)
+        .add_systems(OnExit(AppState::Menu), clear_entities_with_state(AppState::Menu));
 }
 
 #[derive(Component)]
-struct StartGame;
+struct OpenOptions;
+
+// Personality, Difficulty, and HumanMark only matter in one-player games, so they're dimmed and disabled while the pointer is on
+// the "Two Players" button, until it moves back to the "One Player" button
+#[derive(Resource)]
+struct OnePlayerSettingsLocked(bool);
+
+fn unlocked(locked: Res<OnePlayerSettingsLocked>) -> bool {
+    !locked.0
+}
+
+// which of the menu's settings rows (BoardSize, Personality, Difficulty, then HumanMark) the left and right arrow keys
+// change; nothing is focused until the up or down arrow key is pressed
+#[derive(Resource)]
+struct FocusedRow(Option<usize>);
+
+const MENU_ROWS: usize = 4;
+
+#[derive(Component)]
+struct OpenPuzzles;
+
+#[derive(Component)]
+struct OpenStats;
+
+// picks the last game back up, if it was left before it was over (see game::keep_snapshot())
+#[derive(Component)]
+struct ContinueGame;
+
+// plays the last one-player game again, against the moves the computer made in it (see recording.rs)
+#[derive(Component)]
+struct PracticeGame;
+
+// the board editor (see editor.rs) is only for debugging, so its button is only shown in debug builds
+#[derive(Component)]
+struct OpenEditor;
+
+// watching a game hosted on another computer (see spectate.rs), which can't be done from a browser, so there's no button on the web
+#[derive(Component)]
+struct OpenSpectate;
+
+pub fn button<S: Setting>(
+    setting: S,
+    parent: &mut ChildBuilder,
+    font: Handle<Font>,
+    font_size: f32
+) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                border: UiRect::all(Val::Px(2.0)),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+            ..default()
+        },
+        setting
+    )).with_children(|parent| {
+        parent.spawn(
+            TextBundle::from_section(
+                setting.to_string(),
+                TextStyle {
+                    font,
+                    font_size,
+                    color: Color::BLACK,
+                },
+            )
+        );
+    });
+}
+
+pub fn text_button<T: Component>(
+    parent: &mut ChildBuilder,
+    text: impl Into<String>,
+    marker: T,
+    font: Handle<Font>,
+    font_size: f32
+) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                border: UiRect::all(Val::Px(2.0)),
+                padding: UiRect::all(Val::Px(5.0)),
+                margin: UiRect::top(Val::Px(20.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+            ..default()
+        },
+        marker
+    )).with_children(|parent| {
+        parent.spawn(
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font,
+                    font_size,
+                    color: Color::BLACK,
+                },
+            )
+        );
+    });
+}
+
+pub fn settings_row<S>(parent: &mut ChildBuilder, font: Handle<Font>, font_size: f32) where S: Setting + Enumerated<Item = S> {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            ..default()
+        },
+        ..default()
+    }).with_children(|parent| {
+        for variant in S::variants() {
+            button(variant, parent, font.clone(), font_size);
+        }
+    });
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut locked: ResMut<OnePlayerSettingsLocked>,
+    mut focused: ResMut<FocusedRow>,
+) {
+    locked.0 = false;
+    focused.0 = None;
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     let font = asset_server.load("fonts/larabie.otf");
 
     fn word(parent: &mut ChildBuilder, word: [char; 3], font: Handle<Font>) {
@@ -39,7 +206,6 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                             font,
                             font_size: 100.0,
                             color: Color::BLACK,
-                            ..default()
                         },
                     )
                 );
@@ -78,44 +244,11 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 word(parent, ['T', 'A', 'C'], font.clone());
                 word(parent, ['T', 'O', 'E'], font.clone());
 
-                fn button<S: Setting>(
-                    setting: S,
-                    parent: &mut ChildBuilder,
-                    font: Handle<Font>,
-                    font_size: f32
-                ) {
-                    parent.spawn((
-                        ButtonBundle {
-                            style: Style {
-                                border: UiRect::all(Val::Px(2.0)),
-                                padding: UiRect::all(Val::Px(5.0)),
-                                ..default()
-                            },
-                            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
-                            ..default()
-                        },
-                        AppState::Menu,
-                        setting
-                    )).with_children(|parent| {
-                        parent.spawn(
-                            TextBundle::from_section(
-                                setting.to_string(),
-                                TextStyle {
-                                    font,
-                                    font_size,
-                                    color: Color::BLACK,
-                                    ..default()
-                                },
-                            )
-                        );
-                    });
-                }
-
                 parent
                     .spawn(NodeBundle {
                         style: Style {
-                            height: Val::Px(300.0),
-                            margin: UiRect::top(Val::Px(50.0)),
+                            height: Val::Px(360.0),
+                            margin: UiRect::top(Val::Px(20.0)),
                             flex_direction: FlexDirection::Column,
                             align_items: AlignItems::Center,
                             justify_content: JustifyContent::SpaceEvenly,
@@ -124,27 +257,14 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                         ..default()
                     })
                     .with_children(|parent| {
-                        button(GameMode::OnePlayer, parent, font.clone(), 60.0);
+                        // the board size applies to both modes, so it's above them both
+                        settings_row::<BoardSize>(parent, font.clone(), 30.0);
 
-                        fn settings_row<S: Setting>(parent: &mut ChildBuilder, font: Handle<Font>) where S: Enumerated<Item = S> {
-                            parent.spawn(NodeBundle {
-                                style: Style {
-                                    width: Val::Percent(100.0),
-                                    flex_direction: FlexDirection::Row,
-                                    align_items: AlignItems::Center,
-                                    justify_content: JustifyContent::SpaceEvenly,
-                                    ..default()
-                                },
-                                ..default()
-                            }).with_children(|parent| {
-                                for variant in S::variants() {
-                                    button(variant, parent, font.clone(), 40.0);
-                                }
-                            });
-                        }
+                        button(GameMode::OnePlayer, parent, font.clone(), 60.0);
 
-                        settings_row::<Difficulty>(parent, font.clone());
-                        settings_row::<HumanMark>(parent, font.clone());
+                        settings_row::<Personality>(parent, font.clone(), 25.0);
+                        settings_row::<Difficulty>(parent, font.clone(), 40.0);
+                        settings_row::<HumanMark>(parent, font.clone(), 40.0);
 
                         // just a little bit of space to visually separate 1P and 2P modes
                         parent.spawn(NodeBundle {
@@ -154,11 +274,46 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 
                         button(GameMode::TwoPlayers, parent, font.clone(), 60.0);
                     });
+
+                parent
+                    .spawn(NodeBundle {
+                        // with every button showing, there are too many for one row in a small window
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            flex_wrap: FlexWrap::Wrap,
+                            justify_content: JustifyContent::Center,
+                            column_gap: Val::Px(20.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        if GameSnapshot::load(SNAPSHOT_FILE).is_some() {
+                            text_button(parent, "continue", ContinueGame, font.clone(), 30.0);
+                        }
+
+                        if Recording::load(RECORDING_FILE).is_some() {
+                            text_button(parent, "practice", PracticeGame, font.clone(), 30.0);
+                        }
+
+                        text_button(parent, "puzzles", OpenPuzzles, font.clone(), 30.0);
+                        text_button(parent, "options", OpenOptions, font.clone(), 30.0);
+                        text_button(parent, "stats", OpenStats, font.clone(), 30.0);
+                        text_button(parent, "help", OpenHelp, font.clone(), 30.0);
+
+                        if cfg!(debug_assertions) {
+                            text_button(parent, "editor", OpenEditor, font.clone(), 30.0);
+                        }
+
+                        if !cfg!(target_arch = "wasm32") {
+                            text_button(parent, "spectate", OpenSpectate, font.clone(), 30.0);
+                        }
+                    });
             });
     });
 }
 
-fn hover_setting_button<T: Setting>(
+pub fn hover_setting_button<T: Setting>(
     mut buttons: Query<(&Interaction, &mut BorderColor, &T)>,
     selected: Res<T>,
 ) {
@@ -178,8 +333,8 @@ fn hover_setting_button<T: Setting>(
 }
 
 // different from hover_setting_button because we don't want to show the "selected" game mode
-fn hover_start_button(
-    mut buttons: Query<(&Interaction, &mut BorderColor), With<GameMode>>,
+pub fn hover_button<T: Component>(
+    mut buttons: Query<(&Interaction, &mut BorderColor), With<T>>,
 ) {
     for (interaction, mut color) in buttons.iter_mut() {
         match interaction {
@@ -193,7 +348,7 @@ fn hover_start_button(
     }
 }
 
-fn update_setting<T: Setting>(
+pub fn update_setting<T: Setting>(
     query: Query<(&Interaction, &T), Changed<Interaction>>,
     mut setting: ResMut<T>,
 ) {
@@ -205,16 +360,279 @@ fn update_setting<T: Setting>(
     }
 }
 
+fn move_focus(keys: Res<ButtonInput<KeyCode>>, mut focused: ResMut<FocusedRow>) {
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        focused.0 = Some(focused.0.map_or(0, |row| (row + 1) % MENU_ROWS));
+    }
+
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        focused.0 = Some(focused.0.map_or(MENU_ROWS - 1, |row| (row + MENU_ROWS - 1) % MENU_ROWS));
+    }
+}
+
+// when the ROW-th settings row is focused, the left and right arrow keys select the previous and next values of S
+// this runs before hover_setting_button::<S>(), so the newly-selected button is outlined in the same frame
+fn cycle_setting<S: Setting + Enumerated<Item = S>, const ROW: usize>(
+    keys: Res<ButtonInput<KeyCode>>,
+    focused: Res<FocusedRow>,
+    mut setting: ResMut<S>,
+) {
+    if focused.0 != Some(ROW) { return; }
+
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        *setting = setting.next();
+        info!("New setting: {}", *setting);
+    }
+
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        *setting = setting.prev();
+        info!("New setting: {}", *setting);
+    }
+}
+
+// outlines the selected value of the focused row in blue, rather than black
+fn show_focus<S: Setting, const ROW: usize>(
+    mut buttons: Query<(&Interaction, &mut BorderColor, &S)>,
+    focused: Res<FocusedRow>,
+    selected: Res<S>,
+) {
+    if focused.0 != Some(ROW) { return; }
+
+    for (interaction, mut color, value) in buttons.iter_mut() {
+        if *interaction == Interaction::None && *value == *selected {
+            *color = Color::BLUE.into();
+        }
+    }
+}
+
+// picking a Personality sets the Difficulty and GameSpeed it stands for, and changing either of those by hand (here, or
+// on the options screen) goes back to Personality::Custom
+fn apply_personality(
+    mut personality: ResMut<Personality>,
+    mut difficulty: ResMut<Difficulty>,
+    mut game_speed: ResMut<GameSpeed>,
+) {
+    let Some(preset) = personality.preset() else { return; };
+
+    if personality.is_changed() && !personality.is_added() {
+        (*difficulty, *game_speed) = preset;
+        info!("New settings: {} and {} speed, for {}", *difficulty, *game_speed, *personality);
+    } else if preset != (*difficulty, *game_speed) {
+        *personality = Personality::Custom;
+    }
+}
+
+fn lock_one_player_settings(
+    modes: Query<(&Interaction, &GameMode), Changed<Interaction>>,
+    mut locked: ResMut<OnePlayerSettingsLocked>,
+) {
+    for (interaction, mode) in &modes {
+        match (interaction, mode) {
+            (Interaction::None, _) => {}
+            (_, GameMode::OnePlayer) => locked.0 = false,
+            (_, GameMode::TwoPlayers) => locked.0 = true,
+            (_, GameMode::Puzzle) => {}
+        }
+    }
+}
+
+// the buttons for the settings which only matter against the computer
+type OnePlayerSettings = Or<(With<Personality>, With<Difficulty>, With<HumanMark>)>;
+
+fn dim_one_player_settings(
+    locked: Res<OnePlayerSettingsLocked>,
+    mut buttons: Query<(&Children, &mut BorderColor), OnePlayerSettings>,
+    mut texts: Query<&mut Text>,
+) {
+    if !locked.is_changed() { return; }
+
+    let alpha = if locked.0 { 0.3 } else { 1.0 };
+
+    for (children, mut border) in buttons.iter_mut() {
+        if locked.0 {
+            *border = Color::rgba(0.0, 0.0, 0.0, 0.0).into();
+        }
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                for section in text.sections.iter_mut() {
+                    section.style.color.set_a(alpha);
+                }
+            }
+        }
+    }
+}
+
 // When the user presses the "One Player" / "Two Players" button, start the game in OnePlayer / TwoPlayers mode
+// with AutoDifficulty, a one-player game is started at the Difficulty which suits the human's Skill
 fn start(
     mut query: Query<(&Interaction, &GameMode), Changed<Interaction>>,
     mut app_state: ResMut<NextState<AppState>>,
     mut game_mode: ResMut<GameMode>,
+    board_size: Res<BoardSize>,
+    auto_difficulty: Res<AutoDifficulty>,
+    skill: Res<Skill>,
+    mut difficulty: ResMut<Difficulty>,
 ) {
     for (interaction, mode) in &mut query {
         if let Interaction::Pressed = interaction {
             *game_mode = *mode;
-            app_state.set(AppState::Game)
+
+            if *mode == GameMode::OnePlayer && *auto_difficulty == AutoDifficulty::AutoDifficulty {
+                *difficulty = skill.pick();
+                info!("rated {:.0}, so playing {}", skill.rating(), *difficulty);
+            }
+
+            // the larger boards are played on their own screen (see grid_game.rs)
+            match *board_size {
+                BoardSize::ThreeByThree => app_state.set(AppState::Game),
+                BoardSize::FourByFour => app_state.set(AppState::GridGame),
+            }
         }
     }
 }
+
+// When the user presses the "options" button, show the options screen
+fn open_options(
+    query: Query<&Interaction, (Changed<Interaction>, With<OpenOptions>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Options)
+        }
+    }
+}
+
+// When the user presses the "puzzles" button, show the puzzle selection screen
+fn open_puzzles(
+    query: Query<&Interaction, (Changed<Interaction>, With<OpenPuzzles>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Puzzles)
+        }
+    }
+}
+
+// When the user presses the "stats" button, show the stats screen
+fn open_stats(
+    query: Query<&Interaction, (Changed<Interaction>, With<OpenStats>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Stats)
+        }
+    }
+}
+
+// When the user presses the "continue" button, go back to the last game, with the settings it was being played with
+fn continue_game(
+    query: Query<&Interaction, (Changed<Interaction>, With<ContinueGame>)>,
+    mut resume: ResMut<Resume>,
+    mut game_mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
+    mut human_mark: ResMut<HumanMark>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if *interaction != Interaction::Pressed { continue; }
+
+        // the file might have changed since the menu was drawn
+        let Some(snapshot) = GameSnapshot::load(SNAPSHOT_FILE) else { continue; };
+
+        *game_mode = snapshot.game_mode;
+        *difficulty = snapshot.difficulty;
+        *human_mark = snapshot.human_mark;
+        resume.0 = Some(snapshot);
+        app_state.set(AppState::Game);
+    }
+}
+
+// When the user presses the "practice" button, play the last one-player game again, as the same mark, at the same
+// Difficulty
+fn practice_game(
+    query: Query<&Interaction, (Changed<Interaction>, With<PracticeGame>)>,
+    mut practice: ResMut<Practice>,
+    mut game_mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
+    mut human_mark: ResMut<HumanMark>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if *interaction != Interaction::Pressed { continue; }
+
+        // the file might have changed since the menu was drawn
+        let Some(recording) = Recording::load(RECORDING_FILE) else { continue; };
+
+        *game_mode = GameMode::OnePlayer;
+        *difficulty = recording.difficulty;
+        *human_mark = recording.human_mark;
+        practice.0 = Some(recording.moves);
+        app_state.set(AppState::Game);
+    }
+}
+
+// When the user presses the "editor" button, show the board editor
+fn open_editor(
+    query: Query<&Interaction, (Changed<Interaction>, With<OpenEditor>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Editor)
+        }
+    }
+}
+
+// When the user presses the "spectate" button, ask which host to watch
+fn open_spectate(
+    query: Query<&Interaction, (Changed<Interaction>, With<OpenSpectate>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Spectate)
+        }
+    }
+}
+
+// buttons which have just been hovered over, pressed, or left (but not other nodes with an Interaction, like grid cells)
+type ChangedButtons = (Changed<Interaction>, With<Button>);
+
+// plays a quiet tick when the cursor moves onto a button, and a click when a button is pressed
+//
+// Interaction goes from Pressed back to Hovered when the mouse button is released, so we remember each button's
+// previous Interaction, to only play the hover sound when the cursor first arrives on a button
+fn play_button_sounds(
+    mut commands: Commands,
+    buttons: Query<(Entity, &Interaction), ChangedButtons>,
+    mut removed: RemovedComponents<Interaction>,
+    mut previous: Local<HashMap<Entity, Interaction>>,
+    asset_server: Res<AssetServer>,
+    sound: Res<Sound>,
+) {
+    for entity in removed.read() {
+        previous.remove(&entity);
+    }
+
+    for (entity, interaction) in &buttons {
+        let before = previous.insert(entity, *interaction);
+
+        if *sound == Sound::SoundOff { continue; }
+
+        let path = match (before, interaction) {
+            (Some(Interaction::Pressed), Interaction::Hovered) => continue,
+            (_, Interaction::Hovered) => "sounds/hover.wav",
+            (_, Interaction::Pressed) => "sounds/click.wav",
+            (_, Interaction::None) => continue,
+        };
+
+        commands.spawn(AudioBundle {
+            source: asset_server.load(path),
+            settings: PlaybackSettings::DESPAWN,
+        });
+    }
+}
\ No newline at end of file
@@ -0,0 +1,428 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::board::Mark;
+use tic_tac_toe::settings::{AutoDifficulty, AutoRestart, BoardOutline, BoardShake, CellsLeft, CenterBonus, Countdown, FirstMove, Forgiveness, GameSpeed, GridColor, Hosting, LineHints, MarkShading, MatchLength, MoveAnnouncement, MoveNumbers, OccupiedCell, Overtime, PlaceOn, PlacementPitch, PlayStyle, PowerSaving, ResultEmoji, ResultOverlay, ResumeCue, Ripple, SideSwap, Sound, TapToConfirm, Teaching, Theme, ThreatPriority, TieBreak, TieColor, TurnPrompt, UiSize, WinningLine, save_setting};
+use tic_tac_toe::theme_file::ThemeFiles;
+
+use crate::{AppState, clear_entities_with_state, draw_screen};
+use crate::menu::{button, hover_button, hover_setting_button, settings_row, text_button, update_setting};
+
+pub fn plugin(app: &mut App) {
+    app
+        .insert_resource(ThemeFiles::default())
+        .add_systems(Startup, load_theme_files)
+        .add_systems(OnEnter(AppState::Options), setup)
+        .add_systems(Update, update_setting::<MoveNumbers>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<MoveNumbers>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<MatchLength>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<MatchLength>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<SideSwap>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<SideSwap>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<GameSpeed>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<GameSpeed>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Sound>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Sound>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<TapToConfirm>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<TapToConfirm>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<WinningLine>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<WinningLine>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Ripple>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Ripple>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<LineHints>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<LineHints>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<AutoRestart>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<AutoRestart>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Overtime>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Overtime>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Theme>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, pick_theme_file.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_theme_button.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<PlaceOn>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<PlaceOn>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<UiSize>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<UiSize>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<BoardOutline>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<BoardOutline>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<ResultEmoji>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<ResultEmoji>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<BoardShake>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<BoardShake>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Forgiveness>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Forgiveness>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<ResultOverlay>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<ResultOverlay>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<TieBreak>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<TieBreak>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Countdown>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Countdown>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Teaching>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Teaching>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<CenterBonus>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<CenterBonus>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<MarkShading>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<MarkShading>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<GridColor>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<GridColor>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<FirstMove>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<FirstMove>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<PowerSaving>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<PowerSaving>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<PlacementPitch>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<PlacementPitch>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<CellsLeft>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<CellsLeft>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<PlayStyle>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<PlayStyle>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<TurnPrompt>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<TurnPrompt>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<OccupiedCell>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<OccupiedCell>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<ThreatPriority>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<ThreatPriority>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<MoveAnnouncement>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<MoveAnnouncement>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<ResumeCue>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<ResumeCue>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<TieColor>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<TieColor>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<AutoDifficulty>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<AutoDifficulty>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, update_setting::<Hosting>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_setting_button::<Hosting>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, preview_theme.run_if(in_state(AppState::Options)))
+        .add_systems(Update, scroll_settings.run_if(in_state(AppState::Options)))
+        .add_systems(Update, save_grid_color.run_if(in_state(AppState::Options)))
+        .add_systems(Update, hover_button::<BackToMenu>.run_if(in_state(AppState::Options)))
+        .add_systems(Update, back_to_menu.run_if(in_state(AppState::Options)))
+        .add_systems(OnExit(AppState::Options), clear_entities_with_state(AppState::Options));
+}
+
+// where the chosen UiSize is kept between runs of the game
+pub const UI_SIZE_FILE: &str = "tic-tac-toe-ui-size.txt";
+
+// ...and the chosen GridColor
+pub const GRID_COLOR_FILE: &str = "tic-tac-toe-grid-color.txt";
+
+// where the player's own themes are kept, one ThemeFile per file, listed after the built-in Themes
+pub const THEMES_DIR: &str = "tic-tac-toe-themes";
+
+// at Startup, rather than when the App is built, so that any warnings about bad files are logged
+fn load_theme_files(mut theme_files: ResMut<ThemeFiles>) {
+    *theme_files = ThemeFiles::load(THEMES_DIR);
+    info!("loaded {} theme(s) from {}", theme_files.themes.len(), THEMES_DIR);
+}
+
+#[derive(Component)]
+struct BackToMenu;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, theme_files: Res<ThemeFiles>) {
+    let font = asset_server.load("fonts/larabie.otf");
+
+    draw_screen(&mut commands, AppState::Options).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(80.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(5.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(
+                    TextBundle::from_section(
+                        "options",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 60.0,
+                            color: Color::BLACK,
+                        },
+                    )
+                );
+
+                // there are more settings than fit on the screen at once, so they're in a list which can be scrolled
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(600.0),
+                        flex_direction: FlexDirection::Column,
+                        overflow: Overflow::clip_y(),
+                        ..default()
+                    },
+                    ..default()
+                }).with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(5.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        SettingsList::default()
+                    )).with_children(|parent| {
+                        settings_row::<MoveNumbers>(parent, font.clone(), 25.0);
+                        settings_row::<MatchLength>(parent, font.clone(), 25.0);
+                        settings_row::<SideSwap>(parent, font.clone(), 25.0);
+                        settings_row::<GameSpeed>(parent, font.clone(), 25.0);
+                        settings_row::<Sound>(parent, font.clone(), 25.0);
+                        settings_row::<TapToConfirm>(parent, font.clone(), 25.0);
+                        settings_row::<WinningLine>(parent, font.clone(), 25.0);
+                        settings_row::<Ripple>(parent, font.clone(), 25.0);
+                        settings_row::<LineHints>(parent, font.clone(), 25.0);
+                        settings_row::<AutoRestart>(parent, font.clone(), 25.0);
+                        settings_row::<Overtime>(parent, font.clone(), 25.0);
+                        settings_row::<PlaceOn>(parent, font.clone(), 25.0);
+                        settings_row::<UiSize>(parent, font.clone(), 25.0);
+                        settings_row::<BoardOutline>(parent, font.clone(), 25.0);
+                        settings_row::<ResultEmoji>(parent, font.clone(), 25.0);
+                        settings_row::<BoardShake>(parent, font.clone(), 25.0);
+                        settings_row::<Forgiveness>(parent, font.clone(), 25.0);
+                        settings_row::<ResultOverlay>(parent, font.clone(), 25.0);
+                        settings_row::<TieBreak>(parent, font.clone(), 25.0);
+                        settings_row::<Countdown>(parent, font.clone(), 25.0);
+                        settings_row::<Teaching>(parent, font.clone(), 25.0);
+                        settings_row::<CenterBonus>(parent, font.clone(), 25.0);
+                        settings_row::<MarkShading>(parent, font.clone(), 25.0);
+                        settings_row::<GridColor>(parent, font.clone(), 25.0);
+                        settings_row::<FirstMove>(parent, font.clone(), 25.0);
+                        settings_row::<PowerSaving>(parent, font.clone(), 25.0);
+                        settings_row::<PlacementPitch>(parent, font.clone(), 25.0);
+                        settings_row::<CellsLeft>(parent, font.clone(), 25.0);
+                        settings_row::<PlayStyle>(parent, font.clone(), 25.0);
+                        settings_row::<TurnPrompt>(parent, font.clone(), 25.0);
+                        settings_row::<OccupiedCell>(parent, font.clone(), 25.0);
+                        settings_row::<ThreatPriority>(parent, font.clone(), 25.0);
+                        settings_row::<MoveAnnouncement>(parent, font.clone(), 25.0);
+                        settings_row::<ResumeCue>(parent, font.clone(), 25.0);
+                        settings_row::<TieColor>(parent, font.clone(), 25.0);
+                        settings_row::<AutoDifficulty>(parent, font.clone(), 25.0);
+
+                        // browsers can't listen for connections, so there's no hosting on the web
+                        if !cfg!(target_arch = "wasm32") {
+                            settings_row::<Hosting>(parent, font.clone(), 25.0);
+                        }
+
+                        theme_row(parent, font.clone(), 25.0, &theme_files);
+                    });
+                });
+
+                text_button(parent, "back", BackToMenu, font.clone(), 30.0);
+            });
+    });
+}
+
+// tags the miniature board showing the selected Theme, and each of its cells
+#[derive(Component)]
+struct ThemePreview;
+
+#[derive(Component)]
+struct ThemePreviewCell;
+
+#[derive(Component)]
+struct ThemePreviewMark(Mark);
+
+// a button for one of the ThemeFiles, by its index
+#[derive(Component)]
+struct ThemeFileButton(usize);
+
+// like settings_row::<Theme>(), but with a button for each ThemeFile after the built-in Themes,
+// and a small preview of the theme's colors after the buttons
+// the theme is only applied to the game screen when a game starts
+fn theme_row(parent: &mut ChildBuilder, font: Handle<Font>, font_size: f32, theme_files: &ThemeFiles) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            flex_wrap: FlexWrap::Wrap,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            ..default()
+        },
+        ..default()
+    }).with_children(|parent| {
+        for variant in Theme::variants() {
+            button(variant, parent, font.clone(), font_size);
+        }
+
+        for (index, theme_file) in theme_files.themes.iter().enumerate() {
+            parent.spawn((
+                ButtonBundle {
+                    style: Style {
+                        border: UiRect::all(Val::Px(2.0)),
+                        padding: UiRect::all(Val::Px(5.0)),
+                        ..default()
+                    },
+                    background_color: Color::NONE.into(),
+                    ..default()
+                },
+                ThemeFileButton(index)
+            )).with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    theme_file.name.clone(),
+                    TextStyle { font: font.clone(), font_size, color: Color::BLACK }
+                ));
+            });
+        }
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_template_rows: vec![GridTrack::px(14.0); 3],
+                    grid_template_columns: vec![GridTrack::px(14.0); 3],
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            ThemePreview
+        )).with_children(|parent| {
+            const NONE: Val = Val::ZERO;
+            const THIN: Val = Val::Px(1.0);
+
+            let borders = [
+                UiRect::new(NONE, THIN, NONE, THIN), UiRect::new(NONE, NONE, NONE, THIN), UiRect::new(THIN, NONE, NONE, THIN),
+                UiRect::new(NONE, THIN, NONE, NONE), UiRect::new(NONE, NONE, NONE, NONE), UiRect::new(THIN, NONE, NONE, NONE),
+                UiRect::new(NONE, THIN, THIN, NONE), UiRect::new(NONE, NONE, THIN, NONE), UiRect::new(THIN, NONE, THIN, NONE),
+            ];
+
+            // a couple of marks, so their colors can be seen against the background
+            let marks = [Some(Mark::X), None, None, None, Some(Mark::O), None, None, None, Some(Mark::X)];
+
+            for (border, mark) in borders.into_iter().zip(marks) {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style { border, justify_content: JustifyContent::Center, align_items: AlignItems::Center, ..default() },
+                        ..default()
+                    },
+                    ThemePreviewCell
+                )).with_children(|parent| {
+                    if let Some(mark) = mark {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                mark.to_string(),
+                                TextStyle { font: font.clone(), font_size: 12.0, color: mark.color() }
+                            ),
+                            ThemePreviewMark(mark)
+                        ));
+                    }
+                });
+            }
+        });
+    });
+}
+
+// how far the list of settings has been scrolled, in pixels from the top
+#[derive(Component, Default)]
+struct SettingsList {
+    position: f32,
+}
+
+// scrolls the list of settings with the mouse wheel (or a trackpad), as far as its last row, but no further
+fn scroll_settings(
+    mut wheel: EventReader<MouseWheel>,
+    mut lists: Query<(&mut SettingsList, &mut Style, &Parent, &Node)>,
+    nodes: Query<&Node>,
+) {
+    for event in wheel.read() {
+        for (mut list, mut style, parent, node) in lists.iter_mut() {
+            let visible = nodes.get(parent.get()).map(|parent| parent.size().y).unwrap_or_default();
+            let max_scroll = (node.size().y - visible).max(0.0);
+
+            let dy = match event.unit {
+                MouseScrollUnit::Line => event.y * 20.0,
+                MouseScrollUnit::Pixel => event.y,
+            };
+
+            list.position = (list.position - dy).clamp(0.0, max_scroll);
+            style.top = Val::Px(-list.position);
+        }
+    }
+}
+
+// like the UiSize (see apply_ui_size() in main.rs), the GridColor is saved whenever it changes
+fn save_grid_color(grid_color: Res<GridColor>) {
+    if !grid_color.is_changed() || grid_color.is_added() { return; }
+
+    if let Err(error) = save_setting(*grid_color, GRID_COLOR_FILE) {
+        warn!("could not save grid color to {}: {}", GRID_COLOR_FILE, error);
+    }
+}
+
+fn preview_theme(
+    theme: Res<Theme>,
+    theme_files: Res<ThemeFiles>,
+    mut previews: Query<&mut BackgroundColor, With<ThemePreview>>,
+    mut cells: Query<&mut BorderColor, With<ThemePreviewCell>>,
+    mut marks: Query<(&ThemePreviewMark, &mut Text)>,
+) {
+    for mut background in previews.iter_mut() {
+        background.0 = theme_files.background(*theme);
+    }
+
+    for mut border in cells.iter_mut() {
+        border.0 = theme_files.grid(*theme, GridColor::ThemeGrid);
+    }
+
+    for (mark, mut text) in marks.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = theme_files.mark(mark.0);
+        }
+    }
+}
+
+// picking a ThemeFile selects it in place of the Theme, and picking a Theme (see update_setting()) deselects it again
+fn pick_theme_file(
+    file_buttons: Query<(&Interaction, &ThemeFileButton), Changed<Interaction>>,
+    theme_buttons: Query<&Interaction, (Changed<Interaction>, With<Theme>)>,
+    mut theme_files: ResMut<ThemeFiles>,
+) {
+    if theme_buttons.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        theme_files.selected = None;
+    }
+
+    for (interaction, button) in &file_buttons {
+        if *interaction == Interaction::Pressed {
+            theme_files.selected = Some(button.0);
+            info!("New theme: {}", theme_files.themes[button.0].name);
+        }
+    }
+}
+
+// like hover_setting_button::<Theme>(), but with a selected ThemeFile shown as selected instead of the Theme
+fn hover_theme_button(
+    mut theme_buttons: Query<(&Interaction, &mut BorderColor, &Theme)>,
+    mut file_buttons: Query<(&Interaction, &mut BorderColor, &ThemeFileButton), Without<Theme>>,
+    theme: Res<Theme>,
+    theme_files: Res<ThemeFiles>,
+) {
+    let themes = theme_buttons.iter_mut().map(|(interaction, color, value)| (interaction, color, theme_files.selected.is_none() && *value == *theme));
+    let files = file_buttons.iter_mut().map(|(interaction, color, button)| (interaction, color, theme_files.selected == Some(button.0)));
+
+    for (interaction, mut color, selected) in themes.chain(files) {
+        *color = match interaction {
+            Interaction::Hovered => Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            _ if selected => Color::rgba(0.0, 0.0, 0.0, 1.0).into(),
+            _ => Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+        };
+    }
+}
+
+// When the user presses the "back" button, return to the main menu
+fn back_to_menu(
+    query: Query<&Interaction, (Changed<Interaction>, With<BackToMenu>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Menu)
+        }
+    }
+}
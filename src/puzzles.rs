@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use tic_tac_toe::settings::GameMode;
+
+use crate::{AppState, clear_entities_with_state, draw_screen};
+use crate::menu::{hover_button, text_button};
+
+pub fn plugin(app: &mut App) {
+    app
+        .insert_resource(SelectedPuzzle::default())
+        .add_systems(OnEnter(AppState::Puzzles), setup)
+        .add_systems(Update, hover_button::<PuzzleButton>.run_if(in_state(AppState::Puzzles)))
+        .add_systems(Update, hover_button::<BackToMenu>.run_if(in_state(AppState::Puzzles)))
+        .add_systems(Update, select_puzzle.run_if(in_state(AppState::Puzzles)))
+        .add_systems(Update, back_to_menu.run_if(in_state(AppState::Puzzles)))
+        .add_systems(OnExit(AppState::Puzzles), clear_entities_with_state(AppState::Puzzles));
+}
+
+// what the player must achieve with their single move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    Win,  // find a move which forces a win
+    Draw, // find a move which avoids losing
+}
+
+impl std::fmt::Display for Goal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Goal::Win => "find the win",
+            Goal::Draw => "don't lose",
+        })
+    }
+}
+
+pub struct Puzzle {
+    pub name: &'static str,
+    pub notation: &'static str, // see game::Game::from_notation()
+    pub goal: Goal,
+}
+
+pub const PUZZLES: [Puzzle; 4] = [
+    Puzzle { name: "Three in a row", notation: "XX.OO....", goal: Goal::Win },
+    Puzzle { name: "Last line of defense", notation: "XX..O....", goal: Goal::Draw },
+    Puzzle { name: "Fork in the road", notation: "XO..X...O", goal: Goal::Win },
+    Puzzle { name: "Corner trap", notation: "X...O...X", goal: Goal::Draw },
+];
+
+// the index into PUZZLES of the puzzle being played, if any
+#[derive(Resource, Default)]
+pub struct SelectedPuzzle(pub Option<usize>);
+
+#[derive(Component)]
+struct PuzzleButton(usize);
+
+#[derive(Component)]
+struct BackToMenu;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/larabie.otf");
+
+    draw_screen(&mut commands, AppState::Puzzles).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(
+                    TextBundle::from_section(
+                        "puzzles",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 60.0,
+                            color: Color::BLACK,
+                        },
+                    )
+                );
+
+                for (index, puzzle) in PUZZLES.iter().enumerate() {
+                    text_button(parent, puzzle.name, PuzzleButton(index), font.clone(), 40.0);
+                }
+
+                text_button(parent, "back", BackToMenu, font.clone(), 30.0);
+            });
+    });
+}
+
+// When the user picks a puzzle, start the game from that puzzle's position
+fn select_puzzle(
+    query: Query<(&Interaction, &PuzzleButton), Changed<Interaction>>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut selected: ResMut<SelectedPuzzle>,
+) {
+    for (interaction, PuzzleButton(index)) in &query {
+        if let Interaction::Pressed = interaction {
+            *game_mode = GameMode::Puzzle;
+            selected.0 = Some(*index);
+            app_state.set(AppState::Game)
+        }
+    }
+}
+
+// When the user presses the "back" button, return to the main menu
+fn back_to_menu(
+    query: Query<&Interaction, (Changed<Interaction>, With<BackToMenu>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Menu)
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::Enumerated;
+use crate::board::{Cell, Game};
+use crate::settings::{Difficulty, HumanMark};
+
+// the last finished one-player game, kept across runs of the game in a small text file, so that it can be practised
+// against: the computer replays its moves from the recording for as long as the human repeats theirs, and plays as
+// usual (at the same Difficulty) once the human tries something different
+#[derive(Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub human_mark: HumanMark, // always X or O, as HumanRandom is resolved before a game starts
+    pub difficulty: Difficulty,
+    pub moves: Vec<Cell>,
+}
+
+impl Recording {
+    // bumped whenever the format changes, so an older file is thrown away, rather than being misread
+    const VERSION: &'static str = "recording 1";
+
+    // one "name value" pair per line, after a line with the VERSION, with the moves as a transcript, e.g.
+    //
+    //   recording 1
+    //   human X
+    //   difficulty Hard
+    //   moves 1. Xb2 Oa3 2. Xc1 ...
+    pub fn to_text(&self) -> String {
+        let mut game = Game::default();
+
+        for cell in &self.moves {
+            game.set(*cell, game.next_player());
+        }
+
+        [
+            Self::VERSION.to_string(),
+            format!("human {}", self.human_mark),
+            format!("difficulty {}", self.difficulty),
+            format!("moves {}", game.transcript().expect("a recording's moves are made in order")),
+        ].map(|line| line + "\n").concat()
+    }
+
+    // a recording from another version, or which can't be understood, is None
+    pub fn from_text(text: &str) -> Option<Recording> {
+        let mut lines = text.lines();
+
+        if lines.next()? != Self::VERSION { return None; }
+
+        let mut field = |name: &str| lines.next()?.strip_prefix(name)?.strip_prefix(' ').map(str::to_string);
+
+        fn variant<S: Enumerated<Item = S> + std::fmt::Display>(text: &str) -> Option<S> {
+            S::variants().into_iter().find(|variant| variant.to_string() == text)
+        }
+
+        let human_mark = variant::<HumanMark>(&field("human")?)?;
+        let difficulty = variant::<Difficulty>(&field("difficulty")?)?;
+        let game = Game::from_transcript(&field("moves")?).ok()?;
+
+        if human_mark == HumanMark::HumanRandom { return None; }
+
+        Some(Recording { human_mark, difficulty, moves: game.history().to_vec() })
+    }
+
+    // a missing (or unreadable, or outdated) file just means there's no game to practise against
+    pub fn load(path: &str) -> Option<Recording> {
+        std::fs::read_to_string(path).ok().and_then(|text| Recording::from_text(&text))
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+}
\ No newline at end of file
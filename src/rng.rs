@@ -0,0 +1,37 @@
+use bevy::prelude::Resource;
+use rand::prelude::*;
+
+// all of the computer's randomness comes from here, so that games can be reproduced by reusing a seed
+#[derive(Resource)]
+pub struct GameRng(StdRng);
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> GameRng {
+        GameRng(StdRng::seed_from_u64(seed))
+    }
+}
+
+// by default, games are not reproducible
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng(StdRng::from_entropy())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::settings::Difficulty;
+use tic_tac_toe::stats::{Record, Stats, NEVER_GIVE_UP};
+
+use crate::{AppState, clear_entities_with_state, draw_screen};
+use crate::menu::{hover_button, text_button};
+
+pub fn plugin(app: &mut App) {
+    app
+        .add_systems(OnEnter(AppState::Stats), setup)
+        .add_systems(Update, hover_button::<BackToMenu>.run_if(in_state(AppState::Stats)))
+        .add_systems(Update, back_to_menu.run_if(in_state(AppState::Stats)))
+        .add_systems(Update, hover_button::<ExportStats>.run_if(in_state(AppState::Stats)))
+        .add_systems(Update, export_stats.run_if(in_state(AppState::Stats)))
+        .add_systems(OnExit(AppState::Stats), clear_entities_with_state(AppState::Stats));
+}
+
+// where the human's one-player record is kept between runs of the game
+pub const STATS_FILE: &str = "tic-tac-toe-stats.txt";
+
+// where the stats are exported to, as a CSV file, e.g. to be opened in a spreadsheet
+pub const STATS_CSV_FILE: &str = "tic-tac-toe-stats.csv";
+
+#[derive(Component)]
+struct BackToMenu;
+
+#[derive(Component)]
+struct ExportStats;
+
+// the width of a bar showing every game played against one difficulty
+const BAR_WIDTH: f32 = 200.0;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, stats: Res<Stats>) {
+    let font: Handle<Font> = asset_server.load("fonts/larabie.otf");
+
+    let text = |parent: &mut ChildBuilder, value: String, color: Color| {
+        parent.spawn(
+            TextBundle::from_section(value, TextStyle { font: font.clone(), font_size: 30.0, color })
+        );
+    };
+
+    // a horizontal bar, split into wins, draws, and losses, in proportion to how many of each there have been
+    fn bar(parent: &mut ChildBuilder, record: Record) {
+        parent.spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(BAR_WIDTH),
+                height: Val::Px(20.0),
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.1).into(),
+            ..default()
+        }).with_children(|parent| {
+            for (count, color) in [(record.wins, Color::DARK_GREEN), (record.draws, Color::GRAY), (record.losses, Color::RED)] {
+                let fraction = if record.games() == 0 { 0.0 } else { count as f32 / record.games() as f32 };
+
+                parent.spawn(NodeBundle {
+                    style: Style { width: Val::Px(BAR_WIDTH * fraction), height: Val::Percent(100.0), ..default() },
+                    background_color: color.into(),
+                    ..default()
+                });
+            }
+        });
+    }
+
+    draw_screen(&mut commands, AppState::Stats).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(
+                    TextBundle::from_section(
+                        "stats",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 60.0,
+                            color: Color::BLACK,
+                        },
+                    )
+                );
+
+                if stats.is_empty() {
+                    text(parent, "no one-player games played yet".to_string(), Color::BLACK);
+                } else {
+                    // one row per difficulty, with columns for won / drawn / lost, and a bar for the ratio of the three
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            display: Display::Grid,
+                            grid_template_columns: vec![GridTrack::auto(); 5],
+                            column_gap: Val::Px(20.0),
+                            row_gap: Val::Px(10.0),
+                            align_items: AlignItems::Center,
+                            justify_items: JustifyItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    }).with_children(|parent| {
+                        text(parent, "".to_string(), Color::BLACK);
+                        text(parent, "won".to_string(), Color::DARK_GREEN);
+                        text(parent, "drawn".to_string(), Color::GRAY);
+                        text(parent, "lost".to_string(), Color::RED);
+                        text(parent, "".to_string(), Color::BLACK);
+
+                        for difficulty in Difficulty::variants() {
+                            let record = stats.get(difficulty);
+
+                            text(parent, difficulty.to_string(), Color::BLACK);
+                            text(parent, record.wins.to_string(), Color::BLACK);
+                            text(parent, record.draws.to_string(), Color::BLACK);
+                            text(parent, record.losses.to_string(), Color::BLACK);
+                            bar(parent, record);
+                        }
+                    });
+
+                    text(parent, format!("finished {} / resigned {}", stats.finished(), stats.resigned()), Color::BLACK);
+
+                    // the "never give up" achievement, or how close the current run of finished games is to earning it
+                    let achievement = if stats.never_gave_up() {
+                        "never give up: earned".to_string()
+                    } else {
+                        format!("never give up: {} / {}", stats.unbroken(), NEVER_GIVE_UP)
+                    };
+
+                    text(parent, achievement, Color::DARK_GREEN);
+                }
+
+                text_button(parent, "export", ExportStats, font.clone(), 30.0);
+                text_button(parent, "back", BackToMenu, font.clone(), 30.0);
+            });
+    });
+}
+
+// writes the stats to STATS_CSV_FILE
+// there's no file system on the web, so there the CSV is logged to the browser's console instead
+fn export_stats(query: Query<&Interaction, (Changed<Interaction>, With<ExportStats>)>, stats: Res<Stats>) {
+    if !query.iter().any(|interaction| *interaction == Interaction::Pressed) { return; }
+
+    let csv = stats.to_csv();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    match std::fs::write(STATS_CSV_FILE, &csv) {
+        Ok(()) => info!("exported stats to {}", STATS_CSV_FILE),
+        Err(error) => warn!("could not export stats to {}: {}", STATS_CSV_FILE, error),
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    info!("stats:\n{}", csv);
+}
+
+// When the user presses the "back" button, return to the main menu
+fn back_to_menu(
+    query: Query<&Interaction, (Changed<Interaction>, With<BackToMenu>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Menu)
+        }
+    }
+}
\ No newline at end of file
@@ -1,6 +1,9 @@
-use bevy::prelude::{Component, Resource};
+use std::time::Duration;
+
+use bevy::prelude::{Color, Component, Resource};
 
 use crate::Enumerated;
+use crate::board::Cell;
 
 // A Setting is any enum which (1) has a variants() method, (2) can be Displayed, and (3) is a Component
 pub trait Setting: Resource + Component + Enumerated + Clone + Copy + PartialEq + Eq + std::fmt::Display {}
@@ -9,7 +12,8 @@ pub trait Setting: Resource + Component + Enumerated + Clone + Copy + PartialEq
 pub enum HumanMark {
     #[default]
     HumanX,
-    HumanO
+    HumanO,
+    HumanRandom, // picked at the start of each game, see game::resolve_human_mark()
 }
 
 impl std::fmt::Display for HumanMark {
@@ -17,6 +21,7 @@ impl std::fmt::Display for HumanMark {
         write!(f, "{}", match self {
             HumanMark::HumanX => "Human X",
             HumanMark::HumanO => "Human O",
+            HumanMark::HumanRandom => "Random",
         })
     }
 }
@@ -48,6 +53,7 @@ pub enum GameMode {
     OnePlayer,
     #[default]
     TwoPlayers,
+    Puzzle,
 }
 
 impl std::fmt::Display for GameMode {
@@ -55,8 +61,939 @@ impl std::fmt::Display for GameMode {
         write!(f, "{}", match self {
             GameMode::OnePlayer => "One Player",
             GameMode::TwoPlayers => "Two Players",
+            GameMode::Puzzle => "Puzzle",
+        })
+    }
+}
+
+impl Setting for GameMode {}
+
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoveNumbers {
+    #[default]
+    Hidden,
+    Shown,
+    ShownAtEnd, // only once the game is over, so the moves can be looked back over without cluttering the board during play
+}
+
+impl std::fmt::Display for MoveNumbers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            MoveNumbers::Hidden => "No Move #s",
+            MoveNumbers::Shown => "Move #s",
+            MoveNumbers::ShownAtEnd => "Move #s At End",
+        })
+    }
+}
+
+impl Setting for MoveNumbers {}
+
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchLength {
+    #[default]
+    SingleGame,
+    BestOfThree,
+    BestOfFive,
+}
+
+impl MatchLength {
+    pub fn games(&self) -> usize {
+        match self {
+            MatchLength::SingleGame => 1,
+            MatchLength::BestOfThree => 3,
+            MatchLength::BestOfFive => 5,
+        }
+    }
+}
+
+impl std::fmt::Display for MatchLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            MatchLength::SingleGame => "1 Game",
+            MatchLength::BestOfThree => "Best of 3",
+            MatchLength::BestOfFive => "Best of 5",
+        })
+    }
+}
+
+impl Setting for MatchLength {}
+
+// in two-player games, whether players keep the same mark for every game, or alternate (so each gets to go first)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SideSwap {
+    #[default]
+    Keep,
+    Swap,
+}
+
+impl std::fmt::Display for SideSwap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            SideSwap::Keep => "Keep Sides",
+            SideSwap::Swap => "Swap Sides",
+        })
+    }
+}
+
+impl Setting for SideSwap {}
+
+// overall pacing of the game, which scales every animation and delay (like the computer's "thinking" time)
+// except the Countdown, which counts down whole seconds, whatever the speed
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl GameSpeed {
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            GameSpeed::Slow => 2.0,
+            GameSpeed::Normal => 1.0,
+            GameSpeed::Fast => 0.5,
+        }
+    }
+
+    // use this wherever a duration is set, so that it respects the chosen speed
+    pub fn scale(&self, duration: Duration) -> Duration {
+        duration.mul_f32(self.multiplier())
+    }
+}
+
+impl std::fmt::Display for GameSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            GameSpeed::Slow => "Slow",
+            GameSpeed::Normal => "Normal",
+            GameSpeed::Fast => "Fast",
+        })
+    }
+}
+
+impl Setting for GameSpeed {}
+
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sound {
+    #[default]
+    SoundOn,
+    SoundOff,
+}
+
+impl std::fmt::Display for Sound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Sound::SoundOn => "Sound On",
+            Sound::SoundOff => "Sound Off",
+        })
+    }
+}
+
+impl Setting for Sound {}
+
+// on small touch screens, a single tap can easily land on the wrong cell, so optionally require a second, confirming tap
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TapToConfirm {
+    #[default]
+    OneTap,
+    TwoTaps,
+}
+
+impl std::fmt::Display for TapToConfirm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TapToConfirm::OneTap => "One Tap",
+            TapToConfirm::TwoTaps => "Two Taps",
+        })
+    }
+}
+
+impl Setting for TapToConfirm {}
+
+// whether the three cells of the winning line are highlighted when the game ends
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WinningLine {
+    #[default]
+    Highlighted,
+    NotHighlighted,
+}
+
+impl std::fmt::Display for WinningLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            WinningLine::Highlighted => "Show Win",
+            WinningLine::NotHighlighted => "Hide Win",
+        })
+    }
+}
+
+impl Setting for WinningLine {}
+
+// whether a cell briefly flashes in the placing player's color when a mark is placed
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Ripple {
+    #[default]
+    RippleOn,
+    RippleOff,
+}
+
+impl std::fmt::Display for Ripple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Ripple::RippleOn => "Ripple On",
+            Ripple::RippleOff => "Ripple Off",
+        })
+    }
+}
+
+impl Setting for Ripple {}
+
+// whether the lines through the cell under the cursor are faintly highlighted, to help beginners see how cells connect
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineHints {
+    #[default]
+    NoHints,
+    ShowLines,
+}
+
+impl std::fmt::Display for LineHints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            LineHints::NoHints => "No Hints",
+            LineHints::ShowLines => "Line Hints",
+        })
+    }
+}
+
+impl Setting for LineHints {}
+
+// whether a new game starts by itself a few seconds after the game ends (e.g. for an unattended display)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoRestart {
+    #[default]
+    RestartManually,
+    RestartAutomatically,
+}
+
+impl std::fmt::Display for AutoRestart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            AutoRestart::RestartManually => "Manual Restart",
+            AutoRestart::RestartAutomatically => "Auto Restart",
+        })
+    }
+}
+
+impl Setting for AutoRestart {}
+
+// whether a tied game ends there, or the board is cleared and the players swap marks until somebody wins
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overtime {
+    #[default]
+    DrawsStand,
+    SuddenDeath,
+}
+
+impl std::fmt::Display for Overtime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Overtime::DrawsStand => "Draws Stand",
+            Overtime::SuddenDeath => "Sudden Death",
+        })
+    }
+}
+
+impl Setting for Overtime {}
+
+// the colors of the game screen's background and grid lines
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    Classic,
+    Paper,
+    Mint,
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Theme::Classic => "Classic",
+            Theme::Paper => "Paper",
+            Theme::Mint => "Mint",
+        })
+    }
+}
+
+impl Setting for Theme {}
+
+impl Theme {
+    pub fn background(&self) -> Color {
+        match self {
+            Theme::Classic => Color::rgb(0.9, 0.9, 0.9),
+            Theme::Paper => Color::rgb(0.96, 0.93, 0.84),
+            Theme::Mint => Color::rgb(0.85, 0.94, 0.88),
+        }
+    }
+
+    pub fn grid(&self) -> Color {
+        match self {
+            Theme::Classic => Color::BLACK,
+            Theme::Paper => Color::rgb(0.45, 0.3, 0.15),
+            Theme::Mint => Color::rgb(0.1, 0.4, 0.25),
+        }
+    }
+}
+
+// 3x3 is the classic game, on 4x4 a player needs four in a row to win
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoardSize {
+    #[default]
+    ThreeByThree,
+    FourByFour,
+}
+
+impl std::fmt::Display for BoardSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            BoardSize::ThreeByThree => "3 x 3",
+            BoardSize::FourByFour => "4 x 4",
+        })
+    }
+}
+
+impl Setting for BoardSize {}
+
+impl BoardSize {
+    // the number of cells along each side of the board
+    pub fn cells(&self) -> usize {
+        match self {
+            BoardSize::ThreeByThree => 3,
+            BoardSize::FourByFour => 4,
+        }
+    }
+}
+
+// whether a click places a mark when the mouse button goes down, or when it comes back up (over the same cell)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaceOn {
+    #[default]
+    PlaceOnPress,
+    PlaceOnRelease,
+}
+
+impl std::fmt::Display for PlaceOn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            PlaceOn::PlaceOnPress => "On Press",
+            PlaceOn::PlaceOnRelease => "On Release",
+        })
+    }
+}
+
+impl Setting for PlaceOn {}
+
+// how big text and buttons are drawn, for high-resolution displays (the window grows to match)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UiSize {
+    #[default]
+    Normal,
+    Large,
+    Larger,
+}
+
+impl std::fmt::Display for UiSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            UiSize::Normal => "100%",
+            UiSize::Large => "125%",
+            UiSize::Larger => "150%",
+        })
+    }
+}
+
+impl Setting for UiSize {}
+
+impl UiSize {
+    pub fn factor(&self) -> f32 {
+        match self {
+            UiSize::Normal => 1.0,
+            UiSize::Large => 1.25,
+            UiSize::Larger => 1.5,
+        }
+    }
+}
+
+// a setting is saved as its Display text (e.g. "125%"), so it can be loaded again the next time the game runs
+// a missing (or unrecognised) file just means the default setting
+pub fn load_setting<S: Setting + Enumerated<Item = S> + Default>(path: &str) -> S {
+    std::fs::read_to_string(path).ok()
+        .and_then(|text| S::variants().into_iter().find(|variant| variant.to_string() == text.trim()))
+        .unwrap_or_default()
+}
+
+pub fn save_setting<S: Setting>(setting: S, path: &str) -> std::io::Result<()> {
+    std::fs::write(path, setting.to_string())
+}
+
+// whether the board is just the four lines between the cells, or also has a line around the outside
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoardOutline {
+    #[default]
+    GridOnly,
+    Outlined,
+}
+
+impl std::fmt::Display for BoardOutline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            BoardOutline::GridOnly => "Grid Only",
+            BoardOutline::Outlined => "Outlined",
+        })
+    }
+}
+
+impl Setting for BoardOutline {}
+
+// whether a big smiley (or frowny) face is shown above the result when a game ends
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultEmoji {
+    #[default]
+    NoEmoji,
+    ShowEmoji,
+}
+
+impl std::fmt::Display for ResultEmoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ResultEmoji::NoEmoji => "No Emoji",
+            ResultEmoji::ShowEmoji => "Emoji",
+        })
+    }
+}
+
+impl Setting for ResultEmoji {}
+
+// a named preset for the computer player in one-player games, combining how well it plays (Difficulty) with how long
+// it "thinks" before each move (GameSpeed)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Personality {
+    #[default]
+    Custom,
+    CautiousCarl,
+    RecklessRita,
+}
+
+impl std::fmt::Display for Personality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Personality::Custom => "Custom",
+            Personality::CautiousCarl => "Cautious Carl",
+            Personality::RecklessRita => "Reckless Rita",
+        })
+    }
+}
+
+impl Setting for Personality {}
+
+impl Personality {
+    // the Difficulty and GameSpeed this personality stands for, or None for Custom, which leaves both as they are
+    pub fn preset(&self) -> Option<(Difficulty, GameSpeed)> {
+        match self {
+            Personality::Custom => None,
+            Personality::CautiousCarl => Some((Difficulty::Hard, GameSpeed::Slow)),
+            Personality::RecklessRita => Some((Difficulty::Medium, GameSpeed::Fast)),
+        }
+    }
+}
+
+// whether the board shakes when a click is ignored, e.g. while the computer is thinking, or on a cell which is already marked
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoardShake {
+    #[default]
+    ShakeOn,
+    ShakeOff,
+}
+
+impl std::fmt::Display for BoardShake {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            BoardShake::ShakeOn => "Shake On",
+            BoardShake::ShakeOff => "Shake Off",
+        })
+    }
+}
+
+impl Setting for BoardShake {}
+
+// whether the computer goes easy on a human who keeps losing, sometimes passing up a win after a few losses in a row
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Forgiveness {
+    #[default]
+    Relentless,
+    Forgiving,
+}
+
+impl std::fmt::Display for Forgiveness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Forgiveness::Relentless => "Relentless",
+            Forgiveness::Forgiving => "Forgiving",
+        })
+    }
+}
+
+impl Setting for Forgiveness {}
+
+// how much of the final board shows through the game over overlay, so it's easier to see how the game ended
+// with Toast, there's no overlay at all, just a small banner along the top with the result and the buttons
+// (a series result or a puzzle result always gets the overlay, as there's more to say)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultOverlay {
+    #[default]
+    Solid,
+    SeeThrough,
+    Toast,
+}
+
+impl std::fmt::Display for ResultOverlay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ResultOverlay::Solid => "Solid Result",
+            ResultOverlay::SeeThrough => "See-Through",
+            ResultOverlay::Toast => "Toast",
+        })
+    }
+}
+
+impl Setting for ResultOverlay {}
+
+// how the computer chooses between cells it weighs up as equally good (see ai::rank_cells())
+//
+// ties are first put in reading order (top-left to bottom-right), then
+//
+//   LastFound: the last of them is chosen (the bottom-right-most), which is how the computer has always played
+//   FirstFound: the first of them is chosen (the top-left-most)
+//   Random: one of them is chosen at random, but the same way for the same move of the same game
+//   PreferCenter / PreferCorner: the center / a corner is chosen, if it's one of them, else the first of them
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    #[default]
+    LastFound,
+    FirstFound,
+    Random,
+    PreferCenter,
+    PreferCorner,
+}
+
+impl std::fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TieBreak::LastFound => "Last Tie",
+            TieBreak::FirstFound => "First Tie",
+            TieBreak::Random => "Random Tie",
+            TieBreak::PreferCenter => "Center Tie",
+            TieBreak::PreferCorner => "Corner Tie",
+        })
+    }
+}
+
+impl Setting for TieBreak {}
+
+// whether "3... 2... 1..." is counted down on the game screen before the first move can be made
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Countdown {
+    #[default]
+    NoCountdown,
+    CountDown,
+}
+
+impl std::fmt::Display for Countdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Countdown::NoCountdown => "No Countdown",
+            Countdown::CountDown => "Countdown",
+        })
+    }
+}
+
+impl Setting for Countdown {}
+
+// whether each of the human's moves against the computer gets a short note on what it did well, or missed
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Teaching {
+    #[default]
+    NoTeaching,
+    Explain,
+}
+
+impl std::fmt::Display for Teaching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Teaching::NoTeaching => "No Teaching",
+            Teaching::Explain => "Teaching",
+        })
+    }
+}
+
+impl Setting for Teaching {}
+
+// whether the computer favors the center (see ai::rank_cells()), which is strong, but predictable
+//
+// without the bonus, the computer takes the center no more eagerly than any other cell, so its games look more like a
+// person's, but on Hard it still never loses, as the bonus only chooses between cells which are just as safe
+// on Hard, the first move of each player comes from the opening book, which this doesn't change
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CenterBonus {
+    #[default]
+    CenterBonus,
+    NoCenterBonus,
+}
+
+impl std::fmt::Display for CenterBonus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            CenterBonus::CenterBonus => "Center Bonus",
+            CenterBonus::NoCenterBonus => "No Center Bonus",
+        })
+    }
+}
+
+impl Setting for CenterBonus {}
+
+// whether, in one-player games, the computer's marks are drawn a shade lighter than the human's, so it's clear at a glance which marks are whose
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkShading {
+    #[default]
+    PlainMarks,
+    ShadeComputer,
+}
+
+impl std::fmt::Display for MarkShading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            MarkShading::PlainMarks => "Plain Marks",
+            MarkShading::ShadeComputer => "Shade Computer",
+        })
+    }
+}
+
+impl Setting for MarkShading {}
+
+// the color of the grid lines on the game screen, which is the Theme's grid color unless another is picked
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GridColor {
+    #[default]
+    ThemeGrid,
+    Charcoal,
+    Slate,
+    Plum,
+}
+
+impl std::fmt::Display for GridColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            GridColor::ThemeGrid => "Theme Grid",
+            GridColor::Charcoal => "Charcoal",
+            GridColor::Slate => "Slate",
+            GridColor::Plum => "Plum",
+        })
+    }
+}
+
+impl Setting for GridColor {}
+
+impl GridColor {
+    pub fn color(&self, theme: Theme) -> Color {
+        match self {
+            GridColor::ThemeGrid => theme.grid(),
+            GridColor::Charcoal => Color::rgb(0.25, 0.25, 0.25),
+            GridColor::Slate => Color::rgb(0.3, 0.4, 0.55),
+            GridColor::Plum => Color::rgb(0.45, 0.2, 0.45),
+        }
+    }
+}
+
+// a house rule for the very first move of a game, which both the human and the computer have to follow
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FirstMove {
+    #[default]
+    AnyOpening,
+    NoCenterOpening,
+    CornerOpening,
+}
+
+impl std::fmt::Display for FirstMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            FirstMove::AnyOpening => "Any Opening",
+            FirstMove::NoCenterOpening => "No Center Opening",
+            FirstMove::CornerOpening => "Corner Opening",
+        })
+    }
+}
+
+impl Setting for FirstMove {}
+
+impl FirstMove {
+    // whether the first move of a game may be made in this cell
+    pub fn allows(&self, cell: Cell) -> bool {
+        match self {
+            FirstMove::AnyOpening => true,
+            FirstMove::NoCenterOpening => cell != Cell::MiddleMiddle,
+            FirstMove::CornerOpening => cell.is_corner(),
+        }
+    }
+
+    // the rule, as it's explained to a player who breaks it
+    pub fn rule(&self) -> &'static str {
+        match self {
+            FirstMove::AnyOpening => "the first move can be anywhere",
+            FirstMove::NoCenterOpening => "the first move can't be in the center",
+            FirstMove::CornerOpening => "the first move has to be in a corner",
+        }
+    }
+}
+
+// with LowPower, the app only updates when there's input, or something is moving on screen (see main.rs)
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PowerSaving {
+    #[default]
+    FullSpeed,
+    LowPower,
+}
+
+impl std::fmt::Display for PowerSaving {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            PowerSaving::FullSpeed => "Full Speed",
+            PowerSaving::LowPower => "Low Power",
+        })
+    }
+}
+
+impl Setting for PowerSaving {}
+
+// with Sound::SoundOn, each mark is placed with a click, which can be pitched a little higher or lower every time
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlacementPitch {
+    #[default]
+    SamePitch,
+    VariedPitch,
+}
+
+impl std::fmt::Display for PlacementPitch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            PlacementPitch::SamePitch => "Same Pitch",
+            PlacementPitch::VariedPitch => "Varied Pitch",
+        })
+    }
+}
+
+impl Setting for PlacementPitch {}
+
+// a count of the empty cells left on the board, shown during a game
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CellsLeft {
+    #[default]
+    HideCellsLeft,
+    ShowCellsLeft,
+}
+
+impl std::fmt::Display for CellsLeft {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            CellsLeft::HideCellsLeft => "No Cells Left",
+            CellsLeft::ShowCellsLeft => "Cells Left",
+        })
+    }
+}
+
+impl Setting for CellsLeft {}
+
+// how the computer picks its moves, on top of its Difficulty
+// with Mirror, it copies the human's last move, reflected through the center, whenever that cell is free
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlayStyle {
+    #[default]
+    StandardPlay,
+    Mirror,
+}
+
+impl std::fmt::Display for PlayStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            PlayStyle::StandardPlay => "Standard Play",
+            PlayStyle::Mirror => "Mirror Play",
+        })
+    }
+}
+
+impl Setting for PlayStyle {}
+
+// in one-player games, a pulsing "your turn" once the computer has moved, in case its move is missed
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TurnPrompt {
+    #[default]
+    NoTurnPrompt,
+    ShowTurnPrompt,
+}
+
+impl std::fmt::Display for TurnPrompt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TurnPrompt::NoTurnPrompt => "No Turn Prompt",
+            TurnPrompt::ShowTurnPrompt => "Turn Prompt",
+        })
+    }
+}
+
+impl Setting for TurnPrompt {}
+
+// what happens when an occupied cell is clicked: the board shakes (see BoardShake) and thuds (with Sound::SoundOn), the click is only logged, or nothing happens at all
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OccupiedCell {
+    #[default]
+    WarnOccupied,
+    LogOccupied,
+    SilentOccupied,
+}
+
+impl std::fmt::Display for OccupiedCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            OccupiedCell::WarnOccupied => "Warn Occupied",
+            OccupiedCell::LogOccupied => "Log Occupied",
+            OccupiedCell::SilentOccupied => "Silent Occupied",
+        })
+    }
+}
+
+impl Setting for OccupiedCell {}
+
+// on Easy and Medium, whether the computer completes its own line before blocking the human's, or the other way around
+// (which can feel fairer to a beginner), while Hard always wins first
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThreatPriority {
+    #[default]
+    WinFirst,
+    BlockFirst,
+}
+
+impl std::fmt::Display for ThreatPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ThreatPriority::WinFirst => "Win First",
+            ThreatPriority::BlockFirst => "Block First",
+        })
+    }
+}
+
+impl Setting for ThreatPriority {}
+
+// with AnnounceMoves, the computer says where it's moving, e.g. "I'll take the center", just under the board
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoveAnnouncement {
+    #[default]
+    NoAnnouncement,
+    AnnounceMoves,
+}
+
+impl std::fmt::Display for MoveAnnouncement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            MoveAnnouncement::NoAnnouncement => "No Announcements",
+            MoveAnnouncement::AnnounceMoves => "Announce Moves",
+        })
+    }
+}
+
+impl Setting for MoveAnnouncement {}
+
+// with ShowResumeCue, closing the help overlay mid-game (which pauses the game) shows whose turn it is again, and the
+// board hops, so that nobody moves before they're ready
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResumeCue {
+    #[default]
+    NoResumeCue,
+    ShowResumeCue,
+}
+
+impl std::fmt::Display for ResumeCue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ResumeCue::NoResumeCue => "No Resume Cue",
+            ResumeCue::ShowResumeCue => "Resume Cue",
+        })
+    }
+}
+
+impl Setting for ResumeCue {}
+
+// how "It's a tie!" is colored on game over, like a win is colored in the winner's color
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieColor {
+    #[default]
+    ColoredTie,
+    PlainTie,
+}
+
+impl std::fmt::Display for TieColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TieColor::ColoredTie => "Colored Tie",
+            TieColor::PlainTie => "Plain Tie",
+        })
+    }
+}
+
+impl Setting for TieColor {}
+
+impl TieColor {
+    // a muted purple, which reads as neither X's red nor O's blue
+    pub fn color(&self) -> Color {
+        match self {
+            TieColor::ColoredTie => Color::rgb(0.45, 0.35, 0.6),
+            TieColor::PlainTie => Color::BLACK,
+        }
+    }
+}
+
+// with AutoDifficulty, each one-player game is played at the Difficulty which best matches the human's Skill
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoDifficulty {
+    #[default]
+    FixedDifficulty,
+    AutoDifficulty,
+}
+
+impl std::fmt::Display for AutoDifficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            AutoDifficulty::FixedDifficulty => "Fixed Difficulty",
+            AutoDifficulty::AutoDifficulty => "Auto Difficulty",
+        })
+    }
+}
+
+impl Setting for AutoDifficulty {}
+
+// with Hosting, each game on the 3x3 board can be watched from another computer (see broadcast.rs), on broadcast::PORT
+#[derive(Resource, Component, Enumerated, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Hosting {
+    #[default]
+    NotHosting,
+    Hosting,
+}
+
+impl std::fmt::Display for Hosting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Hosting::NotHosting => "Not Hosting",
+            Hosting::Hosting => "Hosting",
         })
     }
 }
 
-impl Setting for GameMode {}
\ No newline at end of file
+impl Setting for Hosting {}
\ No newline at end of file
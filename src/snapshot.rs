@@ -0,0 +1,127 @@
+use crate::Enumerated;
+use crate::board::{Game, Mark};
+use crate::settings::{Difficulty, GameMode, HumanMark};
+
+// everything needed to pick a game back up exactly where it was left, kept across runs of the game in a small text file
+//
+// the board alone (see Game::to_notation()) isn't enough: which settings the game was being played with, and the score
+// of any series, matter too (whose turn it is doesn't, as that follows from the board, see Game::next_player())
+//
+// the file is written by hand, as a few "name value" lines, like the Stats and the Recording, rather than with serde:
+// nothing else in the game needs serde, the snapshot is small enough to read (and fix) in a text editor, and a
+// version line is all it takes to throw away a snapshot from an older format
+#[derive(Clone, PartialEq, Eq)]
+pub struct GameSnapshot {
+    pub notation: String,
+    pub game_mode: GameMode,
+    pub difficulty: Difficulty,
+    pub human_mark: HumanMark, // always X or O, as HumanRandom is resolved before a game starts
+    pub player_one: Mark, // the scoreboard of a two-player series (see game::Series)
+    pub wins: [usize; 2],
+    pub games: usize,
+}
+
+impl GameSnapshot {
+    // bumped whenever the format changes, so an older file is thrown away, rather than being misread
+    const VERSION: &'static str = "snapshot 1";
+
+    // one "name value" pair per line, after a line with the VERSION, e.g.
+    //
+    //   snapshot 1
+    //   board XO..X...O
+    //   mode One Player
+    //   ...
+    pub fn to_text(&self) -> String {
+        let mark = |mark: Mark| mark.to_string();
+
+        [
+            Self::VERSION.to_string(),
+            format!("board {}", self.notation),
+            format!("mode {}", self.game_mode),
+            format!("difficulty {}", self.difficulty),
+            format!("human {}", self.human_mark),
+            format!("series {} {} {} {}", mark(self.player_one), self.wins[0], self.wins[1], self.games),
+        ].map(|line| line + "\n").concat()
+    }
+
+    // a snapshot from another version, or which can't be understood, or which isn't a game in progress, is None
+    pub fn from_text(text: &str) -> Option<GameSnapshot> {
+        let mut lines = text.lines();
+
+        if lines.next()? != Self::VERSION { return None; }
+
+        let mut field = |name: &str| lines.next()?.strip_prefix(name)?.strip_prefix(' ').map(str::to_string);
+
+        fn mark(text: &str) -> Option<Mark> {
+            match text {
+                "X" => Some(Mark::X),
+                "O" => Some(Mark::O),
+                _ => None
+            }
+        }
+
+        fn variant<S: Enumerated<Item = S> + std::fmt::Display>(text: &str) -> Option<S> {
+            S::variants().into_iter().find(|variant| variant.to_string() == text)
+        }
+
+        let notation = field("board")?;
+        let game_mode = variant::<GameMode>(&field("mode")?)?;
+        let difficulty = variant::<Difficulty>(&field("difficulty")?)?;
+        let human_mark = variant::<HumanMark>(&field("human")?)?;
+
+        let series = field("series")?;
+        let [player_one, one, two, games] = series.split_whitespace().collect::<Vec<&str>>()[..] else { return None; };
+        let (player_one, wins, games) = (mark(player_one)?, [one.parse().ok()?, two.parse().ok()?], games.parse().ok()?);
+
+        // the board has to be one which can still be played on
+        let game = Game::from_notation(&notation).ok()?;
+
+        if game.over() || human_mark == HumanMark::HumanRandom {
+            return None;
+        }
+
+        Some(GameSnapshot { notation, game_mode, difficulty, human_mark, player_one, wins, games })
+    }
+
+    // a missing (or unreadable, or outdated) file just means there's no game to pick back up
+    pub fn load(path: &str) -> Option<GameSnapshot> {
+        std::fs::read_to_string(path).ok().and_then(|text| GameSnapshot::from_text(&text))
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    // once a game is over, there's nothing left to pick back up
+    pub fn discard(path: &str) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_snapshot_reads_back_as_it_was_written() {
+        let snapshot = GameSnapshot {
+            notation: "XO..X...O".to_string(),
+            game_mode: GameMode::OnePlayer,
+            difficulty: Difficulty::Hard,
+            human_mark: HumanMark::HumanO,
+            player_one: Mark::X,
+            wins: [1, 2],
+            games: 4,
+        };
+
+        assert!(GameSnapshot::from_text(&snapshot.to_text()) == Some(snapshot.clone()));
+
+        // a finished game, or one from an older version, can't be picked back up
+        let finished = GameSnapshot { notation: "XXXOO....".to_string(), ..snapshot.clone() };
+        assert!(GameSnapshot::from_text(&finished.to_text()).is_none());
+        assert!(GameSnapshot::from_text(&snapshot.to_text().replace("snapshot 1", "snapshot 0")).is_none());
+    }
+}
\ No newline at end of file
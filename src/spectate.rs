@@ -0,0 +1,276 @@
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use tic_tac_toe::Enumerated;
+use tic_tac_toe::board::{Cell, Game};
+use tic_tac_toe::settings::TieColor;
+use tic_tac_toe::broadcast::Spectator;
+
+use crate::{AppState, clear_entities, clear_entities_with_state, draw_screen, spawn_modal};
+use crate::help::help_hidden;
+use crate::menu::{hover_button, text_button};
+
+// watching a game hosted on another computer (see settings::Hosting), read-only: once the host's address is typed in,
+// the board shows each move as the host makes it, and nothing here can be clicked, until the host leaves their game
+pub fn plugin(app: &mut App) {
+    app
+        .insert_resource(Spectating::default())
+        .add_systems(OnEnter(AppState::Spectate), setup)
+        .add_systems(Update, enter_address.run_if(in_state(AppState::Spectate)).run_if(connecting).run_if(help_hidden))
+        .add_systems(Update, follow_game.run_if(in_state(AppState::Spectate)).run_if(watching))
+        .add_systems(Update, hover_button::<Watch>.run_if(in_state(AppState::Spectate)))
+        .add_systems(Update, hover_button::<BackToMenu>.run_if(in_state(AppState::Spectate)))
+        .add_systems(Update, back_to_menu.run_if(in_state(AppState::Spectate)))
+        .add_systems(OnExit(AppState::Spectate), (clear_entities_with_state(AppState::Spectate), clear_entities::<SpectateOverlay>, stop_watching));
+}
+
+// the address is kept when the screen is left, so the same host can be watched again
+#[derive(Resource)]
+struct Spectating {
+    address: String,
+    spectator: Option<Spectator>,
+    host_left: bool,
+}
+
+impl Default for Spectating {
+    fn default() -> Self {
+        Spectating { address: "localhost".to_string(), spectator: None, host_left: false }
+    }
+}
+
+#[derive(Component)]
+struct SpectatedCell(Cell);
+
+// tags the text showing the host's address, as it's typed
+#[derive(Component)]
+struct AddressEntry;
+
+// tags the text showing whose turn it is, who won, or why the host couldn't be reached
+#[derive(Component)]
+struct SpectateStatus;
+
+#[derive(Component)]
+struct Watch;
+
+#[derive(Component)]
+struct BackToMenu;
+
+#[derive(Component)]
+struct SpectateOverlay;
+
+// the longest address which can be typed in, which is plenty for a host name, or an IP address and port
+const MAX_ADDRESS: usize = 40;
+
+fn connecting(spectating: Res<Spectating>) -> bool {
+    spectating.spectator.is_none() && !spectating.host_left
+}
+
+fn watching(spectating: Res<Spectating>) -> bool {
+    spectating.spectator.is_some()
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, spectating: Res<Spectating>) {
+    let font = asset_server.load("fonts/larabie.otf");
+
+    draw_screen(&mut commands, AppState::Spectate).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "spectate",
+                    TextStyle { font: font.clone(), font_size: 60.0, color: Color::BLACK }
+                ));
+
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_template_rows: vec![GridTrack::px(120.0); 3],
+                        grid_template_columns: vec![GridTrack::px(120.0); 3],
+                        ..default()
+                    },
+                    ..default()
+                }).with_children(|parent| {
+                    for cell in Cell::variants() {
+                        let (row, column) = cell.grid_placement();
+
+                        parent.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    grid_row: GridPlacement::start(row),
+                                    grid_column: GridPlacement::start(column),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    border: UiRect::all(Val::Px(2.0)),
+                                    ..default()
+                                },
+                                border_color: Color::BLACK.into(),
+                                ..default()
+                            },
+                            SpectatedCell(cell)
+                        )).with_children(|parent| {
+                            parent.spawn(TextBundle::from_section("", TextStyle { font: font.clone(), font_size: 90.0, color: Color::BLACK }));
+                        });
+                    }
+                });
+
+                parent.spawn((
+                    TextBundle::from_section(
+                        format!("host: {}", spectating.address),
+                        TextStyle { font: font.clone(), font_size: 30.0, color: Color::BLACK }
+                    ),
+                    AddressEntry
+                ));
+
+                parent.spawn((
+                    TextBundle::from_section(
+                        "type the host's address, then press enter",
+                        TextStyle { font: font.clone(), font_size: 30.0, color: Color::BLACK }
+                    ),
+                    SpectateStatus
+                ));
+
+                parent.spawn(NodeBundle {
+                    style: Style { column_gap: Val::Px(20.0), ..default() },
+                    ..default()
+                }).with_children(|parent| {
+                    text_button(parent, "watch", Watch, font.clone(), 30.0);
+                    text_button(parent, "back", BackToMenu, font.clone(), 30.0);
+                });
+            });
+    });
+}
+
+// the host's address is typed in like the notation on the game screen (see game::type_notation()), and connected to
+// with enter, or the "watch" button
+fn enter_address(
+    mut keys: EventReader<KeyboardInput>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<Watch>)>,
+    mut spectating: ResMut<Spectating>,
+    mut addresses: Query<&mut Text, With<AddressEntry>>,
+    mut statuses: Query<&mut Text, (With<SpectateStatus>, Without<AddressEntry>)>,
+) {
+    let mut submitted = buttons.iter().any(|interaction| *interaction == Interaction::Pressed);
+
+    for event in keys.read().filter(|event| event.state == ButtonState::Pressed) {
+        match &event.logical_key {
+            Key::Character(c) if spectating.address.len() < MAX_ADDRESS && c.chars().all(|c| c.is_ascii_alphanumeric() || ".:-".contains(c)) => {
+                spectating.address.push_str(c)
+            }
+            Key::Backspace => { spectating.address.pop(); }
+            Key::Enter => submitted = true,
+            _ => {}
+        }
+    }
+
+    if spectating.is_changed() {
+        for mut text in addresses.iter_mut() {
+            text.sections[0].value = format!("host: {}", spectating.address);
+        }
+    }
+
+    if !submitted { return; }
+
+    // this waits (briefly, see broadcast::CONNECT_TIMEOUT) for the host to answer, which is fine, with nothing else on screen
+    let status = match Spectator::connect(&spectating.address) {
+        Ok(spectator) => {
+            info!("spectating {}", spectating.address);
+            spectating.spectator = Some(spectator);
+            format!("waiting for {}...", spectating.address)
+        }
+        Err(error) => {
+            warn!("could not spectate {}: {}", spectating.address, error);
+            format!("could not connect: {}", error)
+        }
+    };
+
+    for mut text in statuses.iter_mut() {
+        text.sections[0].value = status.clone();
+    }
+}
+
+// whose turn it is, or how the game ended
+fn status(game: &Game) -> String {
+    match game.winner() {
+        Some((winner, _)) => format!("{} has won", winner),
+        None if game.over() => "it's a tie".to_string(),
+        None => format!("{} to move", game.next_player()),
+    }
+}
+
+fn follow_game(
+    mut commands: Commands,
+    mut spectating: ResMut<Spectating>,
+    cells: Query<(&SpectatedCell, &Children)>,
+    mut texts: Query<&mut Text, (Without<SpectateStatus>, Without<AddressEntry>)>,
+    mut statuses: Query<&mut Text, With<SpectateStatus>>,
+    asset_server: Res<AssetServer>,
+    tie_color: Res<TieColor>,
+) {
+    let Some(spectator) = spectating.spectator.as_mut() else { return; };
+
+    match spectator.receive() {
+        Ok(false) => {}
+        Ok(true) => {
+            let game = spectator.game();
+
+            for (SpectatedCell(cell), children) in &cells {
+                let mut iter = texts.iter_many_mut(children);
+
+                while let Some(mut text) = iter.fetch_next() {
+                    let mark = game.get(*cell);
+                    text.sections[0].value = mark.map(|mark| mark.to_string()).unwrap_or_default();
+                    text.sections[0].style.color = mark.map(|mark| mark.color()).unwrap_or(Color::BLACK);
+                }
+            }
+
+            for mut text in statuses.iter_mut() {
+                text.sections[0].value = status(game);
+            }
+        }
+
+        // the host has left their game, so show how it ended, on top of the board as it was left
+        Err(error) => {
+            info!("stopped spectating: {}", error);
+
+            let title = match spectator.game().winner() {
+                Some((winner, _)) => vec![(winner.to_string(), winner.color()), (" wins!".to_string(), Color::BLACK)],
+                None if spectator.game().over() => vec![("It's a tie!".to_string(), tie_color.color())],
+                None => vec![("Game over".to_string(), Color::BLACK)],
+            };
+
+            let font = asset_server.load("fonts/larabie.otf");
+
+            spawn_modal(&mut commands, font, title, vec!["the host has left".to_string()], vec![
+                ("back to menu", Color::RED, BackToMenu),
+            ]).insert(SpectateOverlay);
+
+            spectating.spectator = None;
+            spectating.host_left = true;
+        }
+    }
+}
+
+// leaving the screen closes the connection, so the host stops sending to it
+fn stop_watching(mut spectating: ResMut<Spectating>) {
+    spectating.spectator = None;
+    spectating.host_left = false;
+}
+
+fn back_to_menu(
+    query: Query<&Interaction, (Changed<Interaction>, With<BackToMenu>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &query {
+        if let Interaction::Pressed = interaction {
+            app_state.set(AppState::Menu)
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use bevy::prelude::Resource;
+
+use crate::Enumerated;
+use crate::settings::Difficulty;
+
+// how a one-player game ended, from the human's point of view
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameResult {
+    Win,
+    Draw,
+    Loss,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Record {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Record {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+}
+
+// how many one-player games in a row have to be played to the end, without resigning, for the "never give up" achievement
+pub const NEVER_GIVE_UP: usize = 10;
+
+// the human's record against each difficulty, kept across runs of the game in a small text file
+//
+// alongside it, how many games (at any difficulty) were played to the end, and how many were resigned (which are also
+// counted as losses in the records), along with the current and longest runs of games played to the end
+#[derive(Resource, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Stats {
+    records: [Record; 3],
+    losing_streak: usize, // losses in a row (at any difficulty), which is only kept for as long as the game is running
+    finished: usize,
+    resigned: usize,
+    unbroken: usize,
+    longest_unbroken: usize,
+}
+
+impl Stats {
+    fn index(difficulty: Difficulty) -> usize {
+        match difficulty {
+            Difficulty::Easy => 0,
+            Difficulty::Medium => 1,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    pub fn get(&self, difficulty: Difficulty) -> Record {
+        self.records[Self::index(difficulty)]
+    }
+
+    pub fn record(&mut self, difficulty: Difficulty, result: GameResult) {
+        let record = &mut self.records[Self::index(difficulty)];
+
+        match result {
+            GameResult::Win => record.wins += 1,
+            GameResult::Draw => record.draws += 1,
+            GameResult::Loss => record.losses += 1,
+        }
+
+        self.losing_streak = if result == GameResult::Loss { self.losing_streak + 1 } else { 0 };
+    }
+
+    pub fn losing_streak(&self) -> usize {
+        self.losing_streak
+    }
+
+    // counts how a game ended, alongside record(): either it was resigned, or it was played to the end
+    pub fn record_ending(&mut self, resigned: bool) {
+        if resigned {
+            self.resigned += 1;
+            self.unbroken = 0;
+        } else {
+            self.finished += 1;
+            self.unbroken += 1;
+            self.longest_unbroken = self.longest_unbroken.max(self.unbroken);
+        }
+    }
+
+    pub fn finished(&self) -> usize {
+        self.finished
+    }
+
+    pub fn resigned(&self) -> usize {
+        self.resigned
+    }
+
+    // games in a row played to the end, since the last one which was resigned
+    pub fn unbroken(&self) -> usize {
+        self.unbroken
+    }
+
+    // the "never give up" achievement, for NEVER_GIVE_UP games in a row without resigning, which once earned, is kept
+    pub fn never_gave_up(&self) -> bool {
+        self.longest_unbroken >= NEVER_GIVE_UP
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.iter().all(|record| record.games() == 0)
+    }
+
+    // one line per difficulty, like "Hard 0 12 3" (wins, draws, then losses), then one line for each count of how games
+    // ended, like "resigned 2"
+    pub fn to_text(&self) -> String {
+        let records = Difficulty::variants().into_iter()
+            .map(|difficulty| {
+                let record = self.get(difficulty);
+                format!("{} {} {} {}\n", difficulty, record.wins, record.draws, record.losses)
+            })
+            .collect::<String>();
+
+        let endings = [
+            ("finished", self.finished),
+            ("resigned", self.resigned),
+            ("unbroken", self.unbroken),
+            ("longest", self.longest_unbroken),
+        ].map(|(name, count)| format!("{} {}\n", name, count)).concat();
+
+        records + &endings
+    }
+
+    // the same records as a spreadsheet-friendly CSV, with a header row, then one row per difficulty
+    // with no games played yet, there's just the header
+    pub fn to_csv(&self) -> String {
+        let mut csv = "difficulty,wins,draws,losses\n".to_string();
+
+        if self.is_empty() { return csv; }
+
+        for difficulty in Difficulty::variants() {
+            let record = self.get(difficulty);
+            csv.push_str(&format!("{},{},{},{}\n", difficulty, record.wins, record.draws, record.losses));
+        }
+
+        csv
+    }
+
+    // lines which can't be understood are skipped, so a damaged file loses (some) stats, rather than crashing the game
+    pub fn from_text(text: &str) -> Stats {
+        let mut stats = Stats::default();
+
+        for line in text.lines() {
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+
+            if let [name, count] = words[..] {
+                let Ok(count) = count.parse() else { continue; };
+
+                match name {
+                    "finished" => stats.finished = count,
+                    "resigned" => stats.resigned = count,
+                    "unbroken" => stats.unbroken = count,
+                    "longest" => stats.longest_unbroken = count,
+                    _ => {}
+                }
+
+                continue;
+            }
+
+            let [name, wins, draws, losses] = words[..] else { continue; };
+
+            let Some(difficulty) = Difficulty::variants().into_iter().find(|d| d.to_string() == name) else { continue; };
+
+            if let (Ok(wins), Ok(draws), Ok(losses)) = (wins.parse(), draws.parse(), losses.parse()) {
+                stats.records[Self::index(difficulty)] = Record { wins, draws, losses };
+            }
+        }
+
+        stats
+    }
+
+    // a missing file just means no games have been played yet
+    pub fn load(path: &str) -> Stats {
+        std::fs::read_to_string(path)
+            .map(|text| Stats::from_text(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+}
+
+// an estimate of how well the human plays, as an Elo-style rating, where each Difficulty has a fixed rating of its own
+//
+// a rating equal to a difficulty's means the human should score about 50% against it (counting a draw as half a win),
+// which, as Hard never loses, is what someone who always draws against Hard would reach
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct Skill {
+    rating: f32,
+}
+
+impl Default for Skill {
+    // someone who's never played is assumed to be a match for Medium
+    fn default() -> Self {
+        Skill { rating: Skill::difficulty_rating(Difficulty::Medium) }
+    }
+}
+
+impl Skill {
+    // how much a single game can move the rating, which is high, so that it settles within a handful of games
+    const K: f32 = 64.0;
+
+    fn difficulty_rating(difficulty: Difficulty) -> f32 {
+        match difficulty {
+            Difficulty::Easy => 1000.0,
+            Difficulty::Medium => 1200.0,
+            Difficulty::Hard => 1400.0,
+        }
+    }
+
+    fn score(result: GameResult) -> f32 {
+        match result {
+            GameResult::Win => 1.0,
+            GameResult::Draw => 0.5,
+            GameResult::Loss => 0.0,
+        }
+    }
+
+    // the share of the points the human should get against `difficulty`, from 0.0 (always losing) to 1.0 (always winning)
+    pub fn expected_score(&self, difficulty: Difficulty) -> f32 {
+        1.0 / (1.0 + 10f32.powf((Skill::difficulty_rating(difficulty) - self.rating) / 400.0))
+    }
+
+    pub fn rating(&self) -> f32 {
+        self.rating
+    }
+
+    // a starting estimate from the records kept so far, averaging the rating shown against each difficulty, weighted by
+    // the number of games played at it
+    //
+    // a perfect (or hopeless) record would give an infinite rating, so scores are kept between 5% and 95%
+    pub fn from_stats(stats: &Stats) -> Skill {
+        let (total, games) = Difficulty::variants().into_iter()
+            .map(|difficulty| (difficulty, stats.get(difficulty)))
+            .filter(|(_, record)| record.games() > 0)
+            .fold((0.0, 0), |(total, games), (difficulty, record)| {
+                let score = (record.wins as f32 + record.draws as f32 / 2.0) / record.games() as f32;
+                let score = score.clamp(0.05, 0.95);
+                let rating = Skill::difficulty_rating(difficulty) + 400.0 * (score / (1.0 - score)).log10();
+                (total + rating * record.games() as f32, games + record.games())
+            });
+
+        if games == 0 { Skill::default() } else { Skill { rating: total / games as f32 } }
+    }
+
+    // moves the rating towards the result, by more when the result was unexpected (e.g. a win against Hard)
+    pub fn update(&mut self, difficulty: Difficulty, result: GameResult) {
+        self.rating += Skill::K * (Skill::score(result) - self.expected_score(difficulty));
+    }
+
+    // the difficulty the human should score closest to 50% against
+    pub fn pick(&self) -> Difficulty {
+        Difficulty::variants().into_iter()
+            .min_by(|a, b| (self.expected_score(*a) - 0.5).abs().total_cmp(&(self.expected_score(*b) - 0.5).abs()))
+            .expect("there is at least one difficulty")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_rating_moves_with_each_result() {
+        // against an even match, a win and a loss move the rating by half of K each way, and a draw doesn't move it
+        let even = Skill::default();
+        assert_eq!(even.expected_score(Difficulty::Medium), 0.5);
+
+        let rated = |difficulty: Difficulty, result: GameResult| {
+            let mut skill = even;
+            skill.update(difficulty, result);
+            skill.rating()
+        };
+
+        assert_eq!(rated(Difficulty::Medium, GameResult::Win), 1232.0);
+        assert_eq!(rated(Difficulty::Medium, GameResult::Loss), 1168.0);
+        assert_eq!(rated(Difficulty::Medium, GameResult::Draw), 1200.0);
+
+        // beating Easy was expected, so it's worth less than beating Hard, and a draw against Hard still counts for something
+        assert!(rated(Difficulty::Easy, GameResult::Win) < rated(Difficulty::Hard, GameResult::Win));
+        assert!(rated(Difficulty::Easy, GameResult::Draw) < 1200.0);
+        assert!(rated(Difficulty::Hard, GameResult::Draw) > 1200.0);
+    }
+
+    #[test]
+    fn a_perfect_record_gives_a_finite_rating() {
+        let record = |difficulty: Difficulty, result: GameResult| {
+            let mut stats = Stats::default();
+            for _ in 0..10 {
+                stats.record(difficulty, result);
+            }
+            Skill::from_stats(&stats).rating()
+        };
+
+        // scores are clamped to 5% and 95%, which is 19 to 1 either way, or about 511 points from the difficulty's rating
+        let best = record(Difficulty::Easy, GameResult::Win);
+        let worst = record(Difficulty::Easy, GameResult::Loss);
+
+        assert!(best.is_finite() && worst.is_finite());
+        assert!((best - (1000.0 + 400.0 * 19f32.log10())).abs() < 0.01);
+        assert!((worst - (1000.0 - 400.0 * 19f32.log10())).abs() < 0.01);
+
+        // and with no games at all, the rating starts at Medium's
+        assert_eq!(Skill::from_stats(&Stats::default()), Skill::default());
+    }
+
+    #[test]
+    fn pick_chooses_the_closest_difficulty() {
+        let pick = |rating: f32| Skill { rating }.pick();
+
+        // each difficulty is picked from halfway to the one below it, to halfway to the one above it
+        assert!(pick(500.0) == Difficulty::Easy);
+        assert!(pick(1099.0) == Difficulty::Easy);
+        assert!(pick(1101.0) == Difficulty::Medium);
+        assert!(pick(1200.0) == Difficulty::Medium);
+        assert!(pick(1299.0) == Difficulty::Medium);
+        assert!(pick(1301.0) == Difficulty::Hard);
+        assert!(pick(2000.0) == Difficulty::Hard);
+    }
+
+    #[test]
+    fn resigning_starts_the_run_of_finished_games_again() {
+        let mut stats = Stats::default();
+
+        for _ in 0..NEVER_GIVE_UP - 1 {
+            stats.record_ending(false);
+        }
+
+        stats.record_ending(true);
+        assert_eq!((stats.finished(), stats.resigned(), stats.unbroken()), (NEVER_GIVE_UP - 1, 1, 0));
+        assert!(!stats.never_gave_up());
+
+        for _ in 0..NEVER_GIVE_UP {
+            stats.record_ending(false);
+        }
+
+        assert!(stats.never_gave_up());
+
+        // the achievement is kept after resigning again, and across runs of the game
+        stats.record_ending(true);
+        assert!(stats.never_gave_up() && stats.unbroken() == 0);
+        assert_eq!(Stats::from_text(&stats.to_text()), stats);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use bevy::log::warn;
+use bevy::prelude::{Color, Resource};
+
+use crate::board::Mark;
+use crate::settings::{GridColor, Theme};
+
+// a theme made by the player, rather than one of the built-in Themes, kept in a small text file, e.g.
+//
+//   theme 1
+//   background #1e1e2e
+//   grid #cdd6f4
+//   x #f38ba8
+//   o #89b4fa
+//
+// the colors can be given in any order, but all four are needed
+// the theme is named after its file, so "Midnight.txt" is listed as "Midnight"
+#[derive(Clone, PartialEq, Debug)]
+pub struct ThemeFile {
+    pub name: String,
+    pub background: Color,
+    pub grid: Color,
+    pub x: Color,
+    pub o: Color,
+}
+
+impl ThemeFile {
+    // bumped whenever the format changes, so an older file is skipped, rather than being misread
+    const VERSION: &'static str = "theme 1";
+
+    // the error says what's wrong with the file, so it can be fixed
+    pub fn from_text(name: &str, text: &str) -> Result<ThemeFile, String> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+        if lines.next() != Some(Self::VERSION) {
+            return Err(format!("the first line should be \"{}\"", Self::VERSION));
+        }
+
+        let (mut background, mut grid, mut x, mut o) = (None, None, None, None);
+
+        for line in lines {
+            let (key, value) = line.split_once(' ').ok_or(format!("\"{}\" should be a name and a color", line))?;
+            let color = Color::hex(value.trim()).map_err(|_| format!("\"{}\" is not a color, like #1e1e2e", value.trim()))?;
+
+            let field = match key {
+                "background" => &mut background,
+                "grid" => &mut grid,
+                "x" => &mut x,
+                "o" => &mut o,
+                _ => return Err(format!("\"{}\" should be background, grid, x, or o", key)),
+            };
+
+            if field.replace(color).is_some() {
+                return Err(format!("{} is given more than once", key));
+            }
+        }
+
+        let missing = |key: &str| format!("{} is missing", key);
+
+        Ok(ThemeFile {
+            name: name.to_string(),
+            background: background.ok_or_else(|| missing("background"))?,
+            grid: grid.ok_or_else(|| missing("grid"))?,
+            x: x.ok_or_else(|| missing("x"))?,
+            o: o.ok_or_else(|| missing("o"))?,
+        })
+    }
+
+    pub fn mark(&self, mark: Mark) -> Color {
+        match mark {
+            Mark::X => self.x,
+            Mark::O => self.o,
+        }
+    }
+}
+
+// every ThemeFile found when the game started, and which of them (if any) is used in place of the Theme
+#[derive(Resource, Default)]
+pub struct ThemeFiles {
+    pub themes: Vec<ThemeFile>,
+    pub selected: Option<usize>,
+}
+
+impl ThemeFiles {
+    // every file in the directory is read as a ThemeFile, in order of name
+    // a missing directory just means there are no themes, and a file which can't be understood is skipped, with a warning
+    pub fn load(dir: &str) -> ThemeFiles {
+        let Ok(entries) = std::fs::read_dir(dir) else { return ThemeFiles::default(); };
+
+        let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.is_file()).collect();
+        paths.sort();
+
+        let themes = paths.iter().filter_map(|path| {
+            let read = |path: &Path| {
+                let name = path.file_stem().and_then(|stem| stem.to_str()).ok_or("the file name can't be read")?;
+                let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+                ThemeFile::from_text(name, &text)
+            };
+
+            read(path).map_err(|error| warn!("skipping theme {}: {}", path.display(), error)).ok()
+        }).collect();
+
+        ThemeFiles { themes, selected: None }
+    }
+
+    pub fn selected(&self) -> Option<&ThemeFile> {
+        self.selected.and_then(|index| self.themes.get(index))
+    }
+
+    // the colors of the selected theme file, or of the Theme, if none is selected
+    // a GridColor, if one is picked, still colors the grid lines instead
+
+    pub fn background(&self, theme: Theme) -> Color {
+        self.selected().map_or(theme.background(), |file| file.background)
+    }
+
+    pub fn grid(&self, theme: Theme, grid_color: GridColor) -> Color {
+        match self.selected() {
+            Some(file) if grid_color == GridColor::ThemeGrid => file.grid,
+            _ => grid_color.color(theme),
+        }
+    }
+
+    pub fn mark(&self, mark: Mark) -> Color {
+        self.selected().map_or(mark.color(), |file| file.mark(mark))
+    }
+}
\ No newline at end of file